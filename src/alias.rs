@@ -0,0 +1,126 @@
+//! Aliases that configs can use instead of pinning versions and builds,
+//! resolved to a concrete version or build at install time
+
+use std::{fmt, str::FromStr, sync::LazyLock};
+
+use regex::Regex;
+
+use crate::{
+    version::{Comparator, Op},
+    Error, MCVersionReq, Result,
+};
+
+static LINE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(1|\d{2})\.(\d+)\.x(?:-latest)?$").unwrap());
+
+/// A minecraft version or an alias for one:
+///
+/// - `latest` or `latest-release`: the latest release
+/// - `latest-snapshot`: the latest snapshot, which can also be a release
+/// - `1.20.x` or `1.20.x-latest`: the newest release of `1.20`, also `26.1.x` for year-based releases
+/// - anything else is a version id, like `1.20` or `23w45a`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum VersionAlias {
+    Latest,
+    LatestSnapshot,
+    /// The newest release of `{series}.{major}`, see [`Comparator`]
+    Line {
+        series: u8,
+        major: u8,
+    },
+    Exact(String),
+}
+
+impl VersionAlias {
+    /// The requirement matching every release of a [`VersionAlias::Line`]
+    #[must_use]
+    pub fn requirement(&self) -> Option<MCVersionReq> {
+        match self {
+            Self::Line { series, major } => Some(MCVersionReq {
+                comparators: vec![Comparator {
+                    op: Op::Wildcard,
+                    series: *series,
+                    major: *major,
+                    minor: 0,
+                }],
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for VersionAlias {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        Ok(match s {
+            "" => return Err(Error::UnknownAlias(s.to_owned())),
+            "latest" | "latest-release" => Self::Latest,
+            "latest-snapshot" => Self::LatestSnapshot,
+            _ => match LINE_REGEX.captures(s) {
+                Some(caps) => {
+                    let number = |i: usize| {
+                        caps[i]
+                            .parse()
+                            .map_err(|_| Error::UnknownAlias(s.to_owned()))
+                    };
+                    Self::Line {
+                        series: number(1)?,
+                        major: number(2)?,
+                    }
+                }
+                None => Self::Exact(s.to_owned()),
+            },
+        })
+    }
+}
+
+impl fmt::Display for VersionAlias {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Latest => f.write_str("latest"),
+            Self::LatestSnapshot => f.write_str("latest-snapshot"),
+            Self::Line { series, major } => write!(f, "{series}.{major}.x"),
+            Self::Exact(id) => f.write_str(id),
+        }
+    }
+}
+
+/// A build of a server software like paper or purpur, or an alias for one:
+///
+/// - `latest`: the newest build
+/// - `latest-stable`: the newest build that isn't experimental or failed
+/// - anything else is a build number
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum BuildAlias {
+    Latest,
+    LatestStable,
+    Build(String),
+}
+
+impl FromStr for BuildAlias {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        Ok(match s {
+            "latest" => Self::Latest,
+            "latest-stable" => Self::LatestStable,
+            _ if !s.is_empty() && s.chars().all(|c| c.is_ascii_digit()) => {
+                Self::Build(s.to_owned())
+            }
+            _ => return Err(Error::UnknownAlias(s.to_owned())),
+        })
+    }
+}
+
+impl fmt::Display for BuildAlias {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Latest => f.write_str("latest"),
+            Self::LatestStable => f.write_str("latest-stable"),
+            Self::Build(build) => f.write_str(build),
+        }
+    }
+}
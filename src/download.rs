@@ -0,0 +1,151 @@
+//! Downloading files to disk, verified against their sha1 and size
+
+use std::path::{Component, Path};
+
+use sha1::{Digest, Sha1};
+use tokio::{fs, io::AsyncWriteExt};
+
+use crate::{Error, Result};
+
+/// What [`ensure_file()`] had to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FileStatus {
+    /// The file was downloaded, with its size in bytes
+    Downloaded(u64),
+    /// A valid copy of the file already existed, with its size in bytes
+    Reused(u64),
+}
+
+/// Whether a path taken from a manifest, index or archive stays inside the directory
+/// it's joined to: relative, not empty and without `..`
+#[must_use]
+pub fn is_safe_relative_path(path: &str) -> bool {
+    let mut components = Path::new(path).components().peekable();
+    components.peek().is_some() && components.all(|c| matches!(c, Component::Normal(_)))
+}
+
+/// Lowercase hex sha1 of some bytes
+#[must_use]
+pub fn sha1_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha1::digest(data))
+}
+
+/// Whether the file at `path` exists and has the sha1 and size.
+/// An empty `sha1` or a `size` of 0 aren't checked
+pub async fn is_file_valid(path: &Path, sha1: &str, size: u64) -> Result<bool> {
+    let data = match fs::read(path).await {
+        Ok(data) => data,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok((size == 0 || data.len() as u64 == size)
+        && (sha1.is_empty() || sha1_hex(&data).eq_ignore_ascii_case(sha1)))
+}
+
+/// Downloads `url` to `path`, creating parent directories.
+/// The file is written next to `path` first and only moved there once it's verified,
+/// an empty `sha1` or a `size` of 0 aren't checked.
+/// Returns the size of the file
+pub async fn download_file(
+    client: &reqwest::Client,
+    url: &str,
+    path: &Path,
+    sha1: &str,
+    size: u64,
+) -> Result<u64> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+
+    let mut response = client.get(url).send().await?.error_for_status()?;
+
+    let mut partial_name = path.file_name().unwrap_or_default().to_owned();
+    partial_name.push(".part");
+    let partial = path.with_file_name(partial_name);
+    let mut file = fs::File::create(&partial).await?;
+
+    let mut hasher = Sha1::new();
+    let mut written = 0u64;
+    while let Some(chunk) = response.chunk().await? {
+        hasher.update(&chunk);
+        file.write_all(&chunk).await?;
+        written += chunk.len() as u64;
+    }
+    file.flush().await?;
+    drop(file);
+
+    if let Err(e) = verify(
+        url,
+        &format!("{:x}", hasher.finalize()),
+        written,
+        sha1,
+        size,
+    ) {
+        fs::remove_file(&partial).await?;
+        return Err(e);
+    }
+
+    fs::rename(&partial, path).await?;
+    Ok(written)
+}
+
+/// Downloads `url` to `path` unless a valid copy is already there, see [`download_file()`]
+pub async fn ensure_file(
+    client: &reqwest::Client,
+    url: &str,
+    path: &Path,
+    sha1: &str,
+    size: u64,
+) -> Result<FileStatus> {
+    if is_file_valid(path, sha1, size).await? {
+        return Ok(FileStatus::Reused(fs::metadata(path).await?.len()));
+    }
+
+    Ok(FileStatus::Downloaded(
+        download_file(client, url, path, sha1, size).await?,
+    ))
+}
+
+/// Downloads `url` into memory, verified like [`download_file()`]
+pub async fn download_bytes(
+    client: &reqwest::Client,
+    url: &str,
+    sha1: &str,
+    size: u64,
+) -> Result<Vec<u8>> {
+    let data = client
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+    verify_bytes(url, &data, sha1, size)?;
+    Ok(data.to_vec())
+}
+
+/// Checks downloaded bytes against their expected sha1 and size
+pub fn verify_bytes(url: &str, data: &[u8], sha1: &str, size: u64) -> Result<()> {
+    verify(url, &sha1_hex(data), data.len() as u64, sha1, size)
+}
+
+fn verify(url: &str, actual_sha1: &str, actual_size: u64, sha1: &str, size: u64) -> Result<()> {
+    if size != 0 && actual_size != size {
+        return Err(Error::SizeMismatch {
+            url: url.to_owned(),
+            expected: size,
+            actual: actual_size,
+        });
+    }
+
+    if !sha1.is_empty() && !actual_sha1.eq_ignore_ascii_case(sha1) {
+        return Err(Error::HashMismatch {
+            url: url.to_owned(),
+            expected: sha1.to_owned(),
+            actual: actual_sha1.to_owned(),
+        });
+    }
+
+    Ok(())
+}
@@ -3,6 +3,8 @@ use std::collections::{HashMap, HashSet};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::{MCVersion, MCVersionReqSet};
+
 const API_V1: &str = "https://hangar.papermc.io/api/v1";
 
 #[derive(Error, Debug)]
@@ -19,9 +21,9 @@ pub struct Namespace {
     pub slug: String,
 }
 
-impl ToString for Namespace {
-    fn to_string(&self) -> String {
-        format!("{}/{}", self.owner, self.slug)
+impl std::fmt::Display for Namespace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.owner, self.slug)
     }
 }
 
@@ -173,6 +175,29 @@ pub struct ProjectVersion {
     pub platform_dependencies_formatted: HashMap<Platform, String>,
 }
 
+impl ProjectVersion {
+    /// Minecraft versions this version supports on `platform`, from `platform_dependencies`
+    /// or `platform_dependencies_formatted` if the former is missing.
+    /// Only meaningful for [`Platform::Paper`], proxies list their own versions
+    #[must_use]
+    pub fn supported_versions(&self, platform: &Platform) -> MCVersionReqSet {
+        match (
+            self.platform_dependencies.get(platform),
+            self.platform_dependencies_formatted.get(platform),
+        ) {
+            (Some(list), _) => MCVersionReqSet::from_platform_versions(list),
+            (None, Some(formatted)) => MCVersionReqSet::from_platform_versions_formatted(formatted),
+            (None, None) => MCVersionReqSet::default(),
+        }
+    }
+
+    /// Whether this version supports the minecraft `version` on `platform`
+    #[must_use]
+    pub fn supports(&self, platform: &Platform, version: &MCVersion) -> bool {
+        self.supported_versions(platform).matches(version)
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct PluginDependency {
@@ -200,13 +225,13 @@ impl From<&str> for Platform {
     }
 }
 
-impl ToString for Platform {
-    fn to_string(&self) -> String {
-        match self {
-            Self::Paper => "PAPER".to_owned(),
-            Self::Waterfall => "WATERFALL".to_owned(),
-            Self::Velocity => "VELOCITY".to_owned(),
-        }
+impl std::fmt::Display for Platform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Paper => "PAPER",
+            Self::Waterfall => "WATERFALL",
+            Self::Velocity => "VELOCITY",
+        })
     }
 }
 
@@ -288,8 +313,6 @@ pub enum ReviewState {
     PartiallyReviewed,
 }
 
-///
-
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct VersionsFilter {
     pub limit: i64,
@@ -317,6 +340,19 @@ pub struct ProjectVersionsResponse {
     pub result: Vec<ProjectVersion>,
 }
 
+impl ProjectVersionsResponse {
+    /// Versions that support the minecraft `version` on `platform`
+    pub fn supporting<'a>(
+        &'a self,
+        platform: &'a Platform,
+        version: &'a MCVersion,
+    ) -> impl Iterator<Item = &'a ProjectVersion> {
+        self.result
+            .iter()
+            .filter(move |v| v.supports(platform, version))
+    }
+}
+
 pub async fn fetch_project_versions(
     http_client: &reqwest::Client,
     id: &str,
@@ -398,8 +434,7 @@ pub async fn download_project_version(
 ) -> Result<reqwest::Response, HangarError> {
     Ok(http_client
         .get(format!(
-            "{API_V1}/projects/{id}/versions/{name}/{}/download",
-            platform.to_string()
+            "{API_V1}/projects/{id}/versions/{name}/{platform}/download"
         ))
         .send()
         .await?
@@ -0,0 +1,100 @@
+//! Java versions needed to run minecraft versions, for vanilla and other platforms.
+//! Uses a built-in table so a JDK can be picked before anything is downloaded,
+//! see [`crate::vanilla::VersionInfo::java_requirement()`] to include the version's own info
+
+use serde::{Deserialize, Serialize};
+
+use crate::{MCVersion, Result};
+
+/// Java major versions that can run a minecraft version
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct JavaRequirement {
+    /// Lowest java major version that can run it
+    pub required: u8,
+    /// Highest java major version known to work, `None` if there's no known limit
+    pub maximum: Option<u8>,
+}
+
+impl JavaRequirement {
+    /// Whether a java major version satisfies this requirement
+    #[must_use]
+    pub fn is_satisfied_by(&self, major_version: u8) -> bool {
+        major_version >= self.required && self.maximum.is_none_or(|max| major_version <= max)
+    }
+}
+
+/// What is being run, requirements differ between some platforms
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum JavaPlatform {
+    #[default]
+    Vanilla,
+    Paper,
+    Purpur,
+    Forge,
+    Fabric,
+}
+
+/// `(first version, required, maximum)` - every version uses the last entry at or before it
+type JavaTable = &'static [(&'static str, u8, Option<u8>)];
+
+const VANILLA_JAVA: JavaTable = &[
+    ("rd-132211", 8, None),
+    ("21w19a", 16, None),
+    ("1.18-pre2", 17, None),
+    ("24w14a", 21, None),
+    ("26.1-snapshot-1", 25, None),
+];
+
+/// Forge before 1.17 breaks on anything newer than java 8
+const FORGE_JAVA: JavaTable = &[
+    ("1.1", 8, Some(8)),
+    ("1.17", 16, None),
+    ("1.18", 17, None),
+    ("1.20.5", 21, None),
+    ("26.1", 25, None),
+];
+
+impl JavaPlatform {
+    fn table(self) -> JavaTable {
+        match self {
+            // paper, purpur and fabric follow vanilla
+            Self::Vanilla | Self::Paper | Self::Purpur | Self::Fabric => VANILLA_JAVA,
+            Self::Forge => FORGE_JAVA,
+        }
+    }
+}
+
+/// Get the java requirement of a minecraft version on a platform from the built-in table.
+/// Snapshots are placed using [`MCVersion`]'s ordering
+#[must_use]
+pub fn get_java_requirement(platform: JavaPlatform, version: &MCVersion) -> JavaRequirement {
+    let table = platform.table();
+    let (_, required, maximum) = table
+        .iter()
+        .rev()
+        .find(|(since, ..)| {
+            since
+                .parse::<MCVersion>()
+                .is_ok_and(|since| since <= *version)
+        })
+        .unwrap_or(&table[0]);
+
+    JavaRequirement {
+        required: *required,
+        maximum: *maximum,
+    }
+}
+
+/// Same as [`get_java_requirement()`] but parses a version id
+pub fn get_java_requirement_for_id(platform: JavaPlatform, id: &str) -> Result<JavaRequirement> {
+    Ok(get_java_requirement(platform, &id.parse()?))
+}
+
+impl MCVersion {
+    /// See [`get_java_requirement()`]
+    #[must_use]
+    pub fn java_requirement(&self, platform: JavaPlatform) -> JavaRequirement {
+        get_java_requirement(platform, self)
+    }
+}
@@ -6,13 +6,17 @@
 //!
 //! most functions use a `reqwest::Client` and are async
 
-use lazy_static::lazy_static;
+use std::sync::LazyLock;
+
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+pub mod alias;
+pub mod download;
+pub mod java;
 pub mod version;
-pub use version::{MCVersion, MCVersionReq};
+pub use version::{DropStage, MCVersion, MCVersionReq, MCVersionReqSet};
 pub mod vanilla;
 
 pub mod fabric;
@@ -32,10 +36,34 @@ pub enum Error {
     Request(#[from] reqwest::Error),
     #[error("{0} is an invalid MCVersion")]
     InvalidVersion(String),
+    #[error("{0} is not a known version or alias")]
+    UnknownAlias(String),
     #[error(transparent)]
     Regex(#[from] regex::Error),
     #[error(transparent)]
     XML(#[from] roxmltree::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Zip(#[from] zip::result::ZipError),
+    #[error(transparent)]
+    Lzma(#[from] lzma_rs::error::Error),
+    #[error("{url} has sha1 {actual}, expected {expected}")]
+    HashMismatch {
+        url: String,
+        expected: String,
+        actual: String,
+    },
+    #[error("{0} is not a relative path inside its directory")]
+    UnsafePath(String),
+    #[error("{url} is {actual} bytes, expected {expected}")]
+    SizeMismatch {
+        url: String,
+        expected: u64,
+        actual: u64,
+    },
 }
 
 type Result<T> = core::result::Result<T, Error>;
@@ -47,9 +75,7 @@ pub enum Side {
     Client,
 }
 
-lazy_static! {
-    static ref DOLLAR_REGEX: Regex = Regex::new(r"\$\{(\w+)?\}").unwrap();
-}
+static DOLLAR_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\$\{(\w+)?\}").unwrap());
 
 /// Utility fn for replacing strings containing "${}"
 pub fn dollar_repl<F>(input: &str, replacer: F) -> String
@@ -30,3 +30,26 @@ impl PaperBuild {
         download_papermc_build(client, &self.project_id, &self.version, self.build, file).await
     }
 }
+
+impl PaperBuildsResponse {
+    /// Find a build by number or alias, see [`BuildAlias`].
+    /// `latest-stable` skips builds of the experimental channel
+    pub fn resolve(&self, alias: &BuildAlias) -> Result<&PaperVersionBuild> {
+        let build = match alias {
+            BuildAlias::Latest => self.builds.last(),
+            BuildAlias::LatestStable => self
+                .builds
+                .iter()
+                .rev()
+                .find(|b| matches!(b.channel, PaperChannel::Default)),
+            BuildAlias::Build(id) => self.builds.iter().find(|b| b.build.to_string() == *id),
+        };
+
+        build.ok_or_else(|| {
+            Error::NotFound(format!(
+                "{} {} build {alias}",
+                self.project_id, self.version
+            ))
+        })
+    }
+}
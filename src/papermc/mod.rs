@@ -5,7 +5,7 @@
 //! - Velocity (Proxy)
 //! - Waterfall (Proxy)
 
-use crate::Result;
+use crate::{alias::BuildAlias, Error, Result};
 
 const PAPERMC_URL: &str = "https://api.papermc.io/v2";
 
@@ -78,6 +78,19 @@ pub async fn fetch_papermc_builds(
     Ok(builds)
 }
 
+/// Fetch the build of a version that a build number or alias like `latest-stable` points to
+pub async fn resolve_papermc_build(
+    client: &reqwest::Client,
+    project_id: &str,
+    version: &str,
+    alias: &str,
+) -> Result<PaperVersionBuild> {
+    Ok(fetch_papermc_builds(client, project_id, version)
+        .await?
+        .resolve(&alias.parse()?)?
+        .clone())
+}
+
 pub async fn fetch_papermc_build(
     client: &reqwest::Client,
     project_id: &str,
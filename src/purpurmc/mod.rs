@@ -2,7 +2,7 @@
 
 use serde::{Deserialize, Serialize};
 
-use crate::{Error, Result};
+use crate::{alias::BuildAlias, Error, Result};
 
 pub const PURPURMC_URL: &str = "https://api.purpurmc.org/v2";
 
@@ -24,6 +24,22 @@ impl PurpurVersion {
         self.builds.all.iter().find(|b| b.build == build_id)
     }
 
+    /// Find a build by number or alias, see [`BuildAlias`].
+    /// `latest-stable` skips builds that failed
+    pub fn resolve_build(&self, alias: &BuildAlias) -> Result<&PurpurBuild> {
+        let build = match alias {
+            BuildAlias::Latest => Some(&self.builds.latest),
+            BuildAlias::LatestStable => {
+                self.builds.all.iter().rev().find(|b| b.result == "SUCCESS")
+            }
+            BuildAlias::Build(id) => self.get_build(id),
+        };
+
+        build.ok_or_else(|| {
+            Error::NotFound(format!("PurpurMC ver: {} build: {alias}", self.version))
+        })
+    }
+
     pub async fn download_latest_build(
         &self,
         client: &reqwest::Client,
@@ -166,6 +182,18 @@ pub async fn fetch_purpur_version_short(
         .await?)
 }
 
+/// Fetch the build of a version that a build number or alias like `latest-stable` points to
+pub async fn resolve_purpur_build(
+    client: &reqwest::Client,
+    version: &str,
+    alias: &str,
+) -> Result<PurpurBuild> {
+    Ok(fetch_purpur_version(client, version)
+        .await?
+        .resolve_build(&alias.parse()?)?
+        .clone())
+}
+
 pub async fn fetch_purpur_build(
     client: &reqwest::Client,
     version: &str,
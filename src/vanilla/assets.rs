@@ -1,18 +1,211 @@
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
+use futures::{stream, StreamExt};
 use serde::{Deserialize, Serialize};
+use tokio::fs;
 
-use crate::Result;
+use crate::{
+    download::{ensure_file, is_safe_relative_path, FileStatus},
+    Result,
+};
 
 pub const RESOURCES_URL: &str = "https://resources.download.minecraft.net";
 
 #[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct MCAssetIndex {
+    /// Set by `pre-1.6`, assets need to be copied to `resources` in the game directory
     pub map_to_resources: bool,
+    /// Set by `legacy`, assets need to be copied to `assets/virtual/<index id>`
+    #[serde(rename = "virtual")]
+    pub is_virtual: bool,
     pub objects: HashMap<String, MCAsset>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Where a version expects its assets, named by their path in the index
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AssetLayout {
+    /// Only `assets/objects/<xx>/<hash>`, 1.7.3 and later
+    #[default]
+    Objects,
+    /// Copied to `assets/virtual/<index id>`, the `legacy` index
+    Virtual,
+    /// Copied to `resources` in the game directory, the `pre-1.6` index
+    Resources,
+}
+
+impl AssetLayout {
+    /// The layout of a built-in index, for when the index itself isn't at hand
+    #[must_use]
+    pub fn from_index_id(id: &str) -> Self {
+        match id {
+            "pre-1.6" => Self::Resources,
+            "legacy" => Self::Virtual,
+            _ => Self::Objects,
+        }
+    }
+
+    /// The directory assets are copied to, `${game_assets}` in arguments.
+    /// For [`AssetLayout::Objects`] this is the assets directory itself
+    #[must_use]
+    pub fn directory(
+        self,
+        assets_directory: &Path,
+        game_directory: &Path,
+        index_id: &str,
+    ) -> PathBuf {
+        match self {
+            Self::Objects => assets_directory.to_owned(),
+            Self::Virtual => assets_directory.join("virtual").join(index_id),
+            Self::Resources => game_directory.join("resources"),
+        }
+    }
+}
+
+impl MCAssetIndex {
+    #[must_use]
+    pub fn layout(&self) -> AssetLayout {
+        if self.map_to_resources {
+            AssetLayout::Resources
+        } else if self.is_virtual {
+            AssetLayout::Virtual
+        } else {
+            AssetLayout::Objects
+        }
+    }
+
+    /// Copies the downloaded objects to `directory` under their names,
+    /// for the [`AssetLayout::Virtual`] and [`AssetLayout::Resources`] layouts.
+    /// Files that already have the right size are left alone
+    pub async fn copy_objects(&self, objects_directory: &Path, directory: &Path) -> Result<()> {
+        for (name, asset) in &self.objects {
+            if !is_safe_relative_path(name) {
+                continue;
+            }
+
+            let target = directory.join(name);
+            if fs::metadata(&target)
+                .await
+                .is_ok_and(|meta| meta.len() == asset.size)
+            {
+                continue;
+            }
+
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent).await?;
+            }
+            fs::copy(objects_directory.join(asset.get_path()), &target).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Options of [`MCAssetIndex::download_objects()`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetDownloadOptions {
+    /// Defaults to [`RESOURCES_URL`], can be set to a mirror
+    pub resources_url: String,
+    /// How many assets are downloaded at once
+    pub concurrency: usize,
+    /// How many times a failed asset is tried again before giving up
+    pub retries: u32,
+}
+
+impl Default for AssetDownloadOptions {
+    fn default() -> Self {
+        Self {
+            resources_url: RESOURCES_URL.to_owned(),
+            concurrency: 16,
+            retries: 3,
+        }
+    }
+}
+
+/// Reported after every asset by [`MCAssetIndex::download_objects()`]
+#[derive(Debug, Clone, Copy)]
+pub struct AssetProgress<'a> {
+    pub asset: &'a MCAsset,
+    pub status: FileStatus,
+    /// Assets done so far, including this one
+    pub completed: usize,
+    pub total: usize,
+}
+
+/// What [`MCAssetIndex::download_objects()`] did
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AssetDownloadSummary {
+    pub downloaded: usize,
+    pub bytes_downloaded: u64,
+    /// Assets that already existed with the right hash
+    pub reused: usize,
+    pub bytes_reused: u64,
+}
+
+impl MCAssetIndex {
+    /// Downloads every object to `objects_directory/<xx>/<hash>` concurrently,
+    /// verifying hashes and sizes and skipping objects that are already valid.
+    /// Objects shared by several names are only downloaded once
+    pub async fn download_objects<F>(
+        &self,
+        client: &reqwest::Client,
+        objects_directory: &Path,
+        options: &AssetDownloadOptions,
+        mut on_progress: F,
+    ) -> Result<AssetDownloadSummary>
+    where
+        F: FnMut(AssetProgress),
+    {
+        let mut seen = HashSet::new();
+        let assets: Vec<&MCAsset> = self
+            .objects
+            .values()
+            .filter(|asset| seen.insert(&asset.hash))
+            .collect();
+        let total = assets.len();
+
+        let mut downloads = stream::iter(assets)
+            .map(|asset| async move {
+                let url = format!("{}/{}", options.resources_url, asset.get_path());
+                let path = objects_directory.join(asset.get_path());
+                let status = asset
+                    .download_with_retries(client, &url, &path, options.retries)
+                    .await;
+                (asset, status)
+            })
+            .buffer_unordered(options.concurrency.max(1));
+
+        let mut summary = AssetDownloadSummary::default();
+        while let Some((asset, status)) = downloads.next().await {
+            let status = status?;
+            match status {
+                FileStatus::Downloaded(size) => {
+                    summary.downloaded += 1;
+                    summary.bytes_downloaded += size;
+                }
+                FileStatus::Reused(size) => {
+                    summary.reused += 1;
+                    summary.bytes_reused += size;
+                }
+            }
+
+            on_progress(AssetProgress {
+                asset,
+                status,
+                completed: summary.downloaded + summary.reused,
+                total,
+            });
+        }
+
+        Ok(summary)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MCAsset {
     pub hash: String,
     pub size: u64,
@@ -27,6 +220,30 @@ impl MCAsset {
             .error_for_status()?)
     }
 
+    /// Download to `path` verifying the hash and size, unless a valid copy is already there
+    pub async fn download_to(&self, client: &reqwest::Client, path: &Path) -> Result<FileStatus> {
+        ensure_file(client, &self.get_url(), path, &self.hash, self.size).await
+    }
+
+    async fn download_with_retries(
+        &self,
+        client: &reqwest::Client,
+        url: &str,
+        path: &Path,
+        retries: u32,
+    ) -> Result<FileStatus> {
+        let mut attempt = 0;
+        loop {
+            match ensure_file(client, url, path, &self.hash, self.size).await {
+                Err(_) if attempt < retries => {
+                    attempt += 1;
+                    tokio::time::sleep(Duration::from_millis(500) * attempt).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
     /// get the url for downloading this asset
     #[must_use]
     pub fn get_url(&self) -> String {
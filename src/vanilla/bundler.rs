@@ -0,0 +1,202 @@
+use std::{
+    fs::{self, File},
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+use sha2::{Digest, Sha256};
+use zip::ZipArchive;
+
+use crate::{download::is_safe_relative_path, Error, Result};
+
+/// A file packed in a server bundler jar, a line of `versions.list` or `libraries.list`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BundlerEntry {
+    pub sha256: String,
+    /// Version id or maven coordinates
+    pub id: String,
+    /// Relative to `META-INF/versions` or `META-INF/libraries` in the jar,
+    /// and to `versions` or `libraries` once extracted
+    pub path: String,
+}
+
+/// The contents of a bundler jar, which every vanilla server jar is since 1.18.
+/// It extracts the real server jar and its libraries to the working directory on every start,
+/// [`ServerBundle::extract()`] and [`ServerBundle::args()`] do that once and run the server directly,
+/// see [`ServerJar::prepare()`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerBundle {
+    /// Main class of the real server
+    pub main_class: String,
+    pub versions: Vec<BundlerEntry>,
+    pub libraries: Vec<BundlerEntry>,
+}
+
+impl ServerBundle {
+    /// Whether the jar is a bundler jar
+    pub fn is_bundler(jar: &Path) -> Result<bool> {
+        let archive = ZipArchive::new(File::open(jar)?)?;
+        let is_bundler = archive
+            .file_names()
+            .any(|name| name == "META-INF/versions.list");
+        Ok(is_bundler)
+    }
+
+    /// Reads the lists of a bundler jar
+    pub fn read(jar: &Path) -> Result<Self> {
+        let mut archive = ZipArchive::new(File::open(jar)?)?;
+        let mut read = |name: &str| -> Result<String> {
+            let mut entry = archive
+                .by_name(&format!("META-INF/{name}"))
+                .map_err(|_| Error::NotFound(format!("META-INF/{name} in {}", jar.display())))?;
+            let mut content = String::new();
+            entry.read_to_string(&mut content)?;
+            Ok(content)
+        };
+
+        Ok(Self {
+            main_class: read("main-class")?.trim().to_owned(),
+            versions: parse_list(&read("versions.list")?)?,
+            libraries: parse_list(&read("libraries.list")?)?,
+        })
+    }
+
+    /// Extracts the server jar to `directory/versions` and the libraries to `directory/libraries`,
+    /// verifying their sha256. Files that are already valid aren't extracted again
+    pub fn extract(&self, jar: &Path, directory: &Path) -> Result<()> {
+        let mut archive = ZipArchive::new(File::open(jar)?)?;
+        let entries = self
+            .versions
+            .iter()
+            .map(|entry| ("versions", entry))
+            .chain(self.libraries.iter().map(|entry| ("libraries", entry)));
+
+        for (kind, entry) in entries {
+            let target = directory.join(kind).join(&entry.path);
+            if fs::read(&target).is_ok_and(|data| sha256_hex(&data) == entry.sha256) {
+                continue;
+            }
+
+            let name = format!("META-INF/{kind}/{}", entry.path);
+            let mut file = archive
+                .by_name(&name)
+                .map_err(|_| Error::NotFound(format!("{name} in {}", jar.display())))?;
+            let mut data = vec![];
+            file.read_to_end(&mut data)?;
+            let actual = sha256_hex(&data);
+            if actual != entry.sha256 {
+                return Err(Error::HashMismatch {
+                    url: name,
+                    expected: entry.sha256.clone(),
+                    actual,
+                });
+            }
+
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&target, data)?;
+        }
+
+        Ok(())
+    }
+
+    /// The server jar followed by the libraries, once extracted to `directory`
+    #[must_use]
+    pub fn classpath(&self, directory: &Path) -> Vec<PathBuf> {
+        let versions = self
+            .versions
+            .iter()
+            .map(|entry| directory.join("versions").join(&entry.path));
+        let libraries = self
+            .libraries
+            .iter()
+            .map(|entry| directory.join("libraries").join(&entry.path));
+
+        versions.chain(libraries).collect()
+    }
+
+    /// Arguments for the java executable running the extracted server directly:
+    /// `-cp <classpath> <main class>`. Jvm arguments go before these and server arguments after
+    #[must_use]
+    pub fn args(&self, directory: &Path) -> Vec<String> {
+        let separator = if cfg!(windows) { ";" } else { ":" };
+        let classpath = self
+            .classpath(directory)
+            .iter()
+            .map(|path| path.to_string_lossy().into_owned())
+            .collect::<Vec<_>>()
+            .join(separator);
+
+        vec!["-cp".to_owned(), classpath, self.main_class.clone()]
+    }
+}
+
+/// A server jar prepared to run with [`ServerJar::prepare()`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServerJar {
+    /// A jar run with `-jar`
+    Plain(PathBuf),
+    /// A bundler jar whose contents were extracted, run directly
+    Bundled(ServerBundle),
+}
+
+impl ServerJar {
+    /// Extracts the jar to `directory` if it's a bundler jar, see [`ServerBundle::extract()`]
+    pub async fn prepare(jar: &Path, directory: &Path) -> Result<Self> {
+        let jar = jar.to_owned();
+        let directory = directory.to_owned();
+        tokio::task::spawn_blocking(move || {
+            if !ServerBundle::is_bundler(&jar)? {
+                return Ok(Self::Plain(jar));
+            }
+
+            let bundle = ServerBundle::read(&jar)?;
+            bundle.extract(&jar, &directory)?;
+            Ok(Self::Bundled(bundle))
+        })
+        .await
+        .map_err(io::Error::from)?
+    }
+
+    /// Arguments for the java executable running the jar from `directory`,
+    /// `-jar <jar>` or [`ServerBundle::args()`]
+    #[must_use]
+    pub fn args(&self, directory: &Path) -> Vec<String> {
+        match self {
+            Self::Plain(jar) => vec!["-jar".to_owned(), jar.to_string_lossy().into_owned()],
+            Self::Bundled(bundle) => bundle.args(directory),
+        }
+    }
+}
+
+/// Parses `<sha256>\t<id>\t<path>` lines. Every entry is needed to run the server,
+/// so malformed lines and paths escaping the directory are errors
+fn parse_list(list: &str) -> Result<Vec<BundlerEntry>> {
+    list.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let mut columns = line.split('\t');
+            let mut column = || {
+                columns
+                    .next()
+                    .map(ToOwned::to_owned)
+                    .ok_or_else(|| Error::NotFound(format!("columns of bundler entry `{line}`")))
+            };
+            let entry = BundlerEntry {
+                sha256: column()?,
+                id: column()?,
+                path: column()?,
+            };
+
+            if !is_safe_relative_path(&entry.path) {
+                return Err(Error::UnsafePath(entry.path));
+            }
+            Ok(entry)
+        })
+        .collect()
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
@@ -0,0 +1,240 @@
+use std::path::{Path, PathBuf};
+
+use tokio::fs;
+
+use crate::{download::ensure_file, Result};
+
+use super::{
+    extract_natives, AssetDownloadOptions, AssetDownloadSummary, AssetLayout, DownloadType,
+    MCAssetIndex, PistonRuleMatcher, VersionIndex, VersionInfo,
+};
+
+/// Lays out a version in a `.minecraft`-like directory:
+///
+/// - `versions/<id>/<id>.json` and `versions/<id>/<id>.jar`
+/// - `libraries/<path>` for every library (and native) matching the rules
+/// - `assets/indexes/<id>.json` and `assets/objects/<xx>/<hash>`
+/// - assets copied where old versions expect them, see [`AssetLayout`]
+///
+/// Every file is verified against its sha1, and files that are already valid aren't downloaded again
+pub struct Installer {
+    pub client: reqwest::Client,
+    pub root_directory: PathBuf,
+    /// Where the game runs, `pre-1.6` assets are copied to `resources` in it.
+    /// Defaults to the root directory, keep it the same as [`super::LaunchVariables::game_directory`]
+    pub game_directory: PathBuf,
+    pub matcher: PistonRuleMatcher,
+}
+
+impl Installer {
+    #[must_use]
+    pub fn new(client: reqwest::Client, root_directory: &Path, matcher: PistonRuleMatcher) -> Self {
+        Self {
+            client,
+            root_directory: root_directory.to_owned(),
+            game_directory: root_directory.to_owned(),
+            matcher,
+        }
+    }
+
+    /// Installs a version from the manifest: its json, client jar, libraries, asset index and assets
+    pub async fn install(&self, index: &VersionIndex) -> Result<VersionInfo> {
+        let version = self.install_version_json(index).await?;
+        self.install_files(&version).await?;
+        Ok(version)
+    }
+
+    /// Installs a profile merged with the profiles it inherits from (see [`VersionInfo::inherit()`]),
+    /// writing its json with [`Installer::write_profile()`]
+    pub async fn install_profile(&self, profile: &VersionInfo) -> Result<()> {
+        self.write_profile(profile).await?;
+        self.install_files(profile).await
+    }
+
+    async fn install_files(&self, version: &VersionInfo) -> Result<()> {
+        self.install_client_jar(version).await?;
+        self.install_libraries(version).await?;
+        self.install_logging_config(version).await?;
+        let index = self.install_asset_index(version).await?;
+        self.install_assets(&index).await?;
+        self.install_asset_layout(version, &index, &self.game_directory)
+            .await
+    }
+
+    /// Downloads the version's json from piston-meta to `versions/<id>/<id>.json`,
+    /// verified against the manifest's sha1, and parses it
+    pub async fn install_version_json(&self, index: &VersionIndex) -> Result<VersionInfo> {
+        let path = self.version_json_path(&index.id);
+        // the manifest doesn't list sizes
+        ensure_file(&self.client, &index.url, &path, &index.sha1, 0).await?;
+        Ok(serde_json::from_slice(&fs::read(&path).await?)?)
+    }
+
+    /// Writes `versions/<id>/<id>.json` generated from a merged `inheritsFrom` profile.
+    /// Only meant for merged profiles, which have no published json: the generated file
+    /// isn't byte-for-byte what piston-meta serves, use [`Installer::install_version_json()`]
+    /// for versions from the manifest
+    pub async fn write_profile(&self, profile: &VersionInfo) -> Result<PathBuf> {
+        let path = self.version_json_path(&profile.id);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        fs::write(&path, serde_json::to_vec_pretty(profile)?).await?;
+        Ok(path)
+    }
+
+    fn version_json_path(&self, id: &str) -> PathBuf {
+        self.root_directory
+            .join("versions")
+            .join(id)
+            .join(format!("{id}.json"))
+    }
+
+    /// Downloads the client jar to `versions/<jar>/<jar>.jar`, see [`VersionInfo::jar_id()`]
+    pub async fn install_client_jar(&self, version: &VersionInfo) -> Result<()> {
+        if let Some(client_jar) = version.downloads.get(&DownloadType::Client) {
+            let jar = version.jar_id();
+            let path = self
+                .root_directory
+                .join("versions")
+                .join(jar)
+                .join(format!("{jar}.jar"));
+            client_jar.download_to(&self.client, &path).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Downloads every library and native matching the rules into `libraries`
+    pub async fn install_libraries(&self, version: &VersionInfo) -> Result<()> {
+        let directory = self.root_directory.join("libraries");
+
+        for library in &version.libraries {
+            if !self.matcher.should_download_library(library)? {
+                continue;
+            }
+
+            if let (Some(path), Some(url)) =
+                (library.get_artifact_path(), library.get_artifact_url())
+            {
+                let artifact = library.get_artifact();
+                ensure_file(
+                    &self.client,
+                    &url,
+                    &directory.join(path),
+                    &artifact.sha1,
+                    artifact.size,
+                )
+                .await?;
+            }
+
+            if let Some(native) = self.matcher.get_native_library(library)? {
+                if let Some(path) = &native.path {
+                    native
+                        .download_to(&self.client, &directory.join(path))
+                        .await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Extracts the natives of every library matching the rules into `natives_directory`,
+    /// see [`extract_natives()`]. The libraries need to be installed already
+    pub async fn extract_natives(
+        &self,
+        version: &VersionInfo,
+        natives_directory: &Path,
+    ) -> Result<()> {
+        let directory = self.root_directory.join("libraries");
+
+        for library in &version.libraries {
+            if !self.matcher.should_download_library(library)? {
+                continue;
+            }
+
+            let Some(path) = self
+                .matcher
+                .get_native_library(library)?
+                .and_then(|native| native.path)
+            else {
+                continue;
+            };
+
+            let jar = directory.join(path);
+            let target = natives_directory.to_owned();
+            let exclude = library
+                .extract
+                .as_ref()
+                .map(|extract| extract.exclude.clone())
+                .unwrap_or_default();
+            tokio::task::spawn_blocking(move || extract_natives(&jar, &target, &exclude))
+                .await
+                .map_err(std::io::Error::from)??;
+        }
+
+        Ok(())
+    }
+
+    /// Downloads the client's log4j config to `assets/log_configs`, if the version has one.
+    /// The configs piston-meta lists for clients are already patched against log4shell
+    pub async fn install_logging_config(&self, version: &VersionInfo) -> Result<Option<PathBuf>> {
+        let Some(logging) = &version.logging.client else {
+            return Ok(None);
+        };
+
+        let path = logging.path_in(&self.root_directory.join("assets"));
+        logging.file.download_to(&self.client, &path).await?;
+        Ok(Some(path))
+    }
+
+    /// Downloads the asset index to `assets/indexes/<id>.json`
+    pub async fn install_asset_index(&self, version: &VersionInfo) -> Result<MCAssetIndex> {
+        let id = version.asset_index_id();
+        let path = self
+            .root_directory
+            .join("assets")
+            .join("indexes")
+            .join(format!("{id}.json"));
+
+        version.asset_index.download_to(&self.client, &path).await?;
+
+        Ok(serde_json::from_slice(&fs::read(&path).await?)?)
+    }
+
+    /// Copies the assets of old versions to where they expect them, see [`AssetLayout`].
+    /// The objects need to be installed already
+    pub async fn install_asset_layout(
+        &self,
+        version: &VersionInfo,
+        index: &MCAssetIndex,
+        game_directory: &Path,
+    ) -> Result<()> {
+        let assets_directory = self.root_directory.join("assets");
+        let layout = index.layout();
+        if layout == AssetLayout::Objects {
+            return Ok(());
+        }
+
+        index
+            .copy_objects(
+                &assets_directory.join("objects"),
+                &layout.directory(&assets_directory, game_directory, version.asset_index_id()),
+            )
+            .await
+    }
+
+    /// Downloads every object of the index to `assets/objects`, see [`MCAssetIndex::download_objects()`]
+    pub async fn install_assets(&self, index: &MCAssetIndex) -> Result<AssetDownloadSummary> {
+        let directory = self.root_directory.join("assets").join("objects");
+        index
+            .download_objects(
+                &self.client,
+                &directory,
+                &AssetDownloadOptions::default(),
+                |_| {},
+            )
+            .await
+    }
+}
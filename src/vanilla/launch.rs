@@ -0,0 +1,376 @@
+use std::{
+    collections::HashMap,
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use md5::{Digest, Md5};
+
+use crate::Result;
+
+use super::{AssetLayout, Log4ShellMitigation, PistonRuleMatcher, VersionInfo};
+
+/// Typed values for the `${...}` placeholders in a version's arguments
+#[derive(Debug, Clone, Default)]
+pub struct LaunchVariables {
+    pub auth_player_name: String,
+    pub auth_uuid: String,
+    pub auth_access_token: String,
+    pub auth_xuid: String,
+    pub clientid: String,
+    /// `msa` for microsoft accounts, `legacy` for offline mode
+    pub user_type: String,
+
+    pub launcher_name: String,
+    pub launcher_version: String,
+
+    /// The `.minecraft`-like directory holding `versions`, `libraries` and `assets`
+    pub root_directory: PathBuf,
+    /// Where the game runs and saves worlds, `${game_directory}`
+    pub game_directory: PathBuf,
+    /// Defaults to `versions/<id>/natives` in the root directory
+    pub natives_directory: Option<PathBuf>,
+}
+
+impl LaunchVariables {
+    /// Variables for playing offline (or on offline-mode servers) as `player_name`,
+    /// using the same UUID a vanilla offline-mode server would give the player
+    #[must_use]
+    pub fn offline(player_name: &str, root_directory: &Path) -> Self {
+        Self {
+            auth_player_name: player_name.to_owned(),
+            auth_uuid: offline_uuid(player_name),
+            auth_access_token: "0".to_owned(),
+            user_type: "legacy".to_owned(),
+            launcher_name: env!("CARGO_PKG_NAME").to_owned(),
+            launcher_version: env!("CARGO_PKG_VERSION").to_owned(),
+            root_directory: root_directory.to_owned(),
+            game_directory: root_directory.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    #[must_use]
+    pub fn libraries_directory(&self) -> PathBuf {
+        self.root_directory.join("libraries")
+    }
+
+    #[must_use]
+    pub fn assets_directory(&self) -> PathBuf {
+        self.root_directory.join("assets")
+    }
+
+    #[must_use]
+    pub fn version_directory(&self, id: &str) -> PathBuf {
+        self.root_directory.join("versions").join(id)
+    }
+
+    #[must_use]
+    pub fn natives_directory(&self, id: &str) -> PathBuf {
+        self.natives_directory
+            .clone()
+            .unwrap_or_else(|| self.version_directory(id).join("natives"))
+    }
+}
+
+/// Optional launcher features that rules of arguments can depend on
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LaunchFeatures {
+    /// `is_demo_user`, the game runs in demo mode
+    pub is_demo_user: bool,
+    /// `has_custom_resolution`, the window's width and height
+    pub custom_resolution: Option<(u32, u32)>,
+    /// `has_quick_plays_support`, a file the game logs quick play sessions to
+    pub quick_play_path: Option<String>,
+    /// `is_quick_play_*`, join a world right after starting
+    pub quick_play: Option<QuickPlay>,
+}
+
+/// What quick play joins
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QuickPlay {
+    /// Name of a world in `saves`
+    Singleplayer(String),
+    /// Address of a server, like `mc.example.com:25565`
+    Multiplayer(String),
+    /// Id of a realm
+    Realms(String),
+}
+
+impl LaunchFeatures {
+    /// Whether a feature (as named in rules) is enabled, unknown features never are
+    #[must_use]
+    pub fn is_enabled(&self, feature: &str) -> bool {
+        match feature {
+            "is_demo_user" => self.is_demo_user,
+            "has_custom_resolution" => self.custom_resolution.is_some(),
+            "has_quick_plays_support" => self.quick_play_path.is_some(),
+            "is_quick_play_singleplayer" => {
+                matches!(self.quick_play, Some(QuickPlay::Singleplayer(_)))
+            }
+            "is_quick_play_multiplayer" => {
+                matches!(self.quick_play, Some(QuickPlay::Multiplayer(_)))
+            }
+            "is_quick_play_realms" => matches!(self.quick_play, Some(QuickPlay::Realms(_))),
+            _ => false,
+        }
+    }
+
+    /// Values of the `${...}` placeholders the enabled features' arguments use
+    #[must_use]
+    pub fn variables(&self) -> HashMap<String, String> {
+        let mut variables = vec![];
+
+        if let Some((width, height)) = self.custom_resolution {
+            variables.push(("resolution_width", width.to_string()));
+            variables.push(("resolution_height", height.to_string()));
+        }
+
+        if let Some(path) = &self.quick_play_path {
+            variables.push(("quickPlayPath", path.clone()));
+        }
+
+        match &self.quick_play {
+            Some(QuickPlay::Singleplayer(world)) => {
+                variables.push(("quickPlaySingleplayer", world.clone()));
+            }
+            Some(QuickPlay::Multiplayer(address)) => {
+                variables.push(("quickPlayMultiplayer", address.clone()));
+            }
+            Some(QuickPlay::Realms(realm)) => variables.push(("quickPlayRealms", realm.clone())),
+            None => {}
+        }
+
+        variables
+            .into_iter()
+            .map(|(k, v)| (k.to_owned(), v))
+            .collect()
+    }
+}
+
+/// The UUID offline-mode servers give a player, without dashes.
+/// A version 3 UUID of `OfflinePlayer:<name>`
+#[must_use]
+pub fn offline_uuid(player_name: &str) -> String {
+    let mut hash = Md5::digest(format!("OfflinePlayer:{player_name}"));
+    hash[6] = (hash[6] & 0x0f) | 0x30;
+    hash[8] = (hash[8] & 0x3f) | 0x80;
+
+    format!("{hash:x}")
+}
+
+/// Everything needed to start a vanilla client
+#[derive(Debug, Clone)]
+pub struct LaunchPlan {
+    pub main_class: String,
+    pub jvm_args: Vec<String>,
+    pub game_args: Vec<String>,
+    /// Library jars followed by the client jar
+    pub classpath: Vec<PathBuf>,
+    /// Where native libraries need to be extracted to
+    pub natives_directory: PathBuf,
+    pub working_directory: PathBuf,
+}
+
+impl LaunchPlan {
+    /// Resolves the classpath and all arguments of `version`
+    pub fn build(
+        version: &VersionInfo,
+        matcher: &PistonRuleMatcher,
+        vars: &LaunchVariables,
+    ) -> Result<Self> {
+        let libraries_directory = vars.libraries_directory();
+        let mut classpath: Vec<PathBuf> = vec![];
+        for library in &version.libraries {
+            if !matcher.should_download_library(library)? {
+                continue;
+            }
+
+            if let Some(path) = library.get_artifact_path() {
+                let path = libraries_directory.join(path);
+                if !classpath.contains(&path) {
+                    classpath.push(path);
+                }
+            }
+        }
+        let jar = version.jar_id();
+        classpath.push(vars.version_directory(jar).join(format!("{jar}.jar")));
+
+        let natives_directory = vars.natives_directory(&version.id);
+        let map = Self::variables(version, matcher, vars, &classpath, &natives_directory);
+        let (mut jvm_args, game_args) = matcher.build_version_args(version, &map)?;
+        if let Some(logging) = &version.logging.client {
+            jvm_args.push(logging.argument_for(&logging.path_in(&vars.assets_directory())));
+        }
+        if let Some(Log4ShellMitigation::JvmArgument(argument)) = version.log4shell_mitigation() {
+            jvm_args.push(argument);
+        }
+
+        Ok(Self {
+            main_class: version.main_class.clone(),
+            jvm_args,
+            game_args,
+            classpath,
+            natives_directory,
+            working_directory: vars.game_directory.clone(),
+        })
+    }
+
+    fn variables(
+        version: &VersionInfo,
+        matcher: &PistonRuleMatcher,
+        vars: &LaunchVariables,
+        classpath: &[PathBuf],
+        natives_directory: &Path,
+    ) -> HashMap<String, String> {
+        let separator = classpath_separator(matcher);
+        let path = |p: &Path| p.to_string_lossy().into_owned();
+
+        let classpath = classpath
+            .iter()
+            .map(|p| path(p))
+            .collect::<Vec<_>>()
+            .join(separator);
+
+        let assets_index_name = version.asset_index_id();
+        let game_assets = AssetLayout::from_index_id(assets_index_name).directory(
+            &vars.assets_directory(),
+            &vars.game_directory,
+            assets_index_name,
+        );
+
+        [
+            ("auth_player_name", vars.auth_player_name.clone()),
+            ("auth_uuid", vars.auth_uuid.clone()),
+            ("auth_access_token", vars.auth_access_token.clone()),
+            ("auth_xuid", vars.auth_xuid.clone()),
+            ("clientid", vars.clientid.clone()),
+            ("user_type", vars.user_type.clone()),
+            ("user_properties", "{}".to_owned()),
+            (
+                "auth_session",
+                format!("token:{}:{}", vars.auth_access_token, vars.auth_uuid),
+            ),
+            ("launcher_name", vars.launcher_name.clone()),
+            ("launcher_version", vars.launcher_version.clone()),
+            ("version_name", version.id.clone()),
+            ("version_type", version.version_type.to_string()),
+            ("game_directory", path(&vars.game_directory)),
+            ("assets_root", path(&vars.assets_directory())),
+            ("assets_index_name", assets_index_name.to_owned()),
+            ("game_assets", path(&game_assets)),
+            ("library_directory", path(&vars.libraries_directory())),
+            ("natives_directory", path(natives_directory)),
+            ("classpath", classpath),
+            ("classpath_separator", separator.to_owned()),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_owned(), v))
+        .chain(matcher.features.variables())
+        .collect()
+    }
+
+    /// Arguments for the java executable: jvm arguments, main class, game arguments
+    #[must_use]
+    pub fn args(&self) -> Vec<String> {
+        let mut args = self.jvm_args.clone();
+        args.push(self.main_class.clone());
+        args.extend(self.game_args.iter().cloned());
+        args
+    }
+
+    /// A command running `java` with [`Self::args()`] in the working directory
+    #[must_use]
+    pub fn command<S: AsRef<OsStr>>(&self, java: S) -> Command {
+        let mut command = Command::new(java);
+        command
+            .args(self.args())
+            .current_dir(&self.working_directory);
+        command
+    }
+}
+
+fn classpath_separator(matcher: &PistonRuleMatcher) -> &'static str {
+    if matcher.os.name == "windows" {
+        ";"
+    } else {
+        ":"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offline_uuid_matches_servers() {
+        assert_eq!(offline_uuid("Notch"), "b50ad385829d3141a2167e7d7539ba7f");
+    }
+
+    #[test]
+    fn builds_classpath_and_arguments() {
+        let library = |name: &str, path: &str, rules: serde_json::Value| {
+            serde_json::json!({
+                "name": name,
+                "downloads": { "artifact": { "path": path, "sha1": "", "size": 0, "url": "" } },
+                "rules": rules,
+            })
+        };
+        let version: VersionInfo = serde_json::from_value(serde_json::json!({
+            "id": "1.20.4",
+            "type": "release",
+            "mainClass": "net.minecraft.client.main.Main",
+            "assets": "12",
+            "assetIndex": { "id": "12", "sha1": "", "size": 0, "totalSize": 0, "url": "" },
+            "downloads": {},
+            "complianceLevel": 1,
+            "minimumLauncherVersion": 21,
+            "releaseTime": "2023-12-07T12:56:20+00:00",
+            "time": "2023-12-07T12:56:20+00:00",
+            "javaVersion": { "component": "java-runtime-gamma", "majorVersion": 17 },
+            "libraries": [
+                library("com.mojang:logging:1.1.1", "com/mojang/logging/1.1.1/logging-1.1.1.jar", serde_json::json!([])),
+                library(
+                    "ca.weblite:java-objc-bridge:1.1",
+                    "ca/weblite/java-objc-bridge/1.1/java-objc-bridge-1.1.jar",
+                    serde_json::json!([{ "action": "allow", "os": { "name": "osx" } }]),
+                ),
+                library("org.joml:joml:1.10.5", "org/joml/joml/1.10.5/joml-1.10.5.jar", serde_json::json!([])),
+                library("com.mojang:logging:1.1.1", "com/mojang/logging/1.1.1/logging-1.1.1.jar", serde_json::json!([])),
+            ],
+            "arguments": {
+                "game": ["--username", "${auth_player_name}"],
+                "jvm": ["-Djava.library.path=${natives_directory}", "-cp", "${classpath}"],
+            },
+        }))
+        .unwrap();
+
+        let root = Path::new("/games/mc");
+        let matcher =
+            PistonRuleMatcher::new("linux".to_owned(), "x86_64".to_owned(), String::new());
+        let vars = LaunchVariables::offline("Notch", root);
+        let plan = LaunchPlan::build(&version, &matcher, &vars).unwrap();
+
+        let classpath = [
+            "libraries/com/mojang/logging/1.1.1/logging-1.1.1.jar",
+            "libraries/org/joml/joml/1.10.5/joml-1.10.5.jar",
+            "versions/1.20.4/1.20.4.jar",
+        ]
+        .map(|path| root.join(path));
+        assert_eq!(plan.classpath, classpath);
+
+        let natives = root.join("versions/1.20.4/natives");
+        assert_eq!(plan.natives_directory, natives);
+        assert_eq!(
+            plan.jvm_args,
+            [
+                format!("-Djava.library.path={}", natives.display()),
+                "-cp".to_owned(),
+                classpath.map(|p| p.display().to_string()).join(":"),
+            ]
+        );
+        assert_eq!(plan.game_args, ["--username", "Notch"]);
+        assert_eq!(plan.working_directory, root);
+    }
+}
@@ -0,0 +1,134 @@
+//! Mitigations for CVE-2021-44228 (log4shell), following mojang's guidance.
+//! Affects 1.7 up to 1.18.1, which updated log4j.
+//!
+//! Clients only need the jvm argument of 1.17 and 1.18, piston-meta already
+//! lists patched logging configs for them. The patched configs are for servers
+
+use std::path::{Path, PathBuf};
+
+use crate::{download::FileStatus, MCVersion, Result};
+
+use super::{PistonFile, ServerJar, VersionInfo, VersionLoggingInfo};
+
+const NO_LOOKUPS_ARGUMENT: &str = "-Dlog4j2.formatMsgNoLookups=true";
+
+/// What a version needs to not be vulnerable to log4shell
+#[derive(Debug, Clone)]
+pub enum Log4ShellMitigation {
+    /// Add a jvm argument, 1.17 up to 1.18.1
+    JvmArgument(String),
+    /// Use a patched server log4j config, 1.7 up to 1.17
+    Config(VersionLoggingInfo),
+}
+
+/// Get the mitigation a version needs, `None` if it isn't affected
+#[must_use]
+pub fn get_log4shell_mitigation(version: &MCVersion) -> Option<Log4ShellMitigation> {
+    let config = |id: &str, sha1: &str| {
+        Log4ShellMitigation::Config(VersionLoggingInfo {
+            argument: "-Dlog4j.configurationFile=${path}".to_owned(),
+            file: PistonFile {
+                url: format!("https://launcher.mojang.com/v1/objects/{sha1}/{id}"),
+                sha1: sha1.to_owned(),
+                id: Some(id.to_owned()),
+                ..Default::default()
+            },
+            logging_type: "log4j2-xml".to_owned(),
+        })
+    };
+
+    if version < &MCVersion::new(7, 0) || version >= &MCVersion::new(18, 1) {
+        None
+    } else if version >= &MCVersion::new(17, 0) {
+        Some(Log4ShellMitigation::JvmArgument(
+            NO_LOOKUPS_ARGUMENT.to_owned(),
+        ))
+    } else if version >= &MCVersion::new(12, 0) {
+        Some(config(
+            "log4j2_112-116.xml",
+            "02937d122c86ce73319ef9975b58896fc1b491d1",
+        ))
+    } else {
+        Some(config(
+            "log4j2_17-111.xml",
+            "dd2b723346a8dcd48e7f4d245f6bf09e98db9696",
+        ))
+    }
+}
+
+impl Log4ShellMitigation {
+    /// The jvm argument to add, with the config expected in `directory`
+    #[must_use]
+    pub fn jvm_argument(&self, directory: &Path) -> String {
+        match self {
+            Self::JvmArgument(argument) => argument.clone(),
+            Self::Config(config) => config.argument_for(&directory.join(config_name(config))),
+        }
+    }
+
+    /// Where the patched config goes in `directory`
+    #[must_use]
+    pub fn config_path(&self, directory: &Path) -> Option<PathBuf> {
+        match self {
+            Self::JvmArgument(_) => None,
+            Self::Config(config) => Some(directory.join(config_name(config))),
+        }
+    }
+
+    /// Download the patched config to `directory`, if this mitigation has one
+    pub async fn install(
+        &self,
+        client: &reqwest::Client,
+        directory: &Path,
+    ) -> Result<Option<FileStatus>> {
+        match self {
+            Self::JvmArgument(_) => Ok(None),
+            Self::Config(config) => Ok(Some(
+                config
+                    .file
+                    .download_to(client, &directory.join(config_name(config)))
+                    .await?,
+            )),
+        }
+    }
+}
+
+fn config_name(config: &VersionLoggingInfo) -> &str {
+    config.file.id.as_deref().unwrap_or("log4j2.xml")
+}
+
+impl MCVersion {
+    /// See [`get_log4shell_mitigation()`]
+    #[must_use]
+    pub fn log4shell_mitigation(&self) -> Option<Log4ShellMitigation> {
+        get_log4shell_mitigation(self)
+    }
+}
+
+impl VersionInfo {
+    /// See [`get_log4shell_mitigation()`]
+    #[must_use]
+    pub fn log4shell_mitigation(&self) -> Option<Log4ShellMitigation> {
+        get_log4shell_mitigation(&self.id.parse().ok()?)
+    }
+}
+
+/// Arguments for the java executable running a server jar from `directory`,
+/// with the log4shell mitigation of `version`. Versions that need a patched config
+/// get it downloaded to `directory`.
+/// The jar needs to be prepared first, see [`ServerJar::prepare()`]
+pub async fn server_args(
+    client: &reqwest::Client,
+    version: &MCVersion,
+    jar: &ServerJar,
+    directory: &Path,
+) -> Result<Vec<String>> {
+    let mut args = vec![];
+    if let Some(mitigation) = version.log4shell_mitigation() {
+        mitigation.install(client, directory).await?;
+        args.push(mitigation.jvm_argument(directory));
+    }
+
+    args.extend(jar.args(directory));
+    Ok(args)
+}
@@ -1,13 +1,15 @@
+use std::fmt;
+
 use serde::{Deserialize, Serialize};
 
 /// The version manifest, from piston-meta
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct VersionManifest {
     pub latest: LatestVersions,
     pub versions: Vec<VersionIndex>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct LatestVersions {
     pub release: String,
     pub snapshot: String,
@@ -15,7 +17,7 @@ pub struct LatestVersions {
 
 /// The version info from a manifest's versions list
 /// Use [`Self::fetch()`] to get an [`VersionInfo`] which contains more info about the version
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct VersionIndex {
     pub id: String,
@@ -29,7 +31,7 @@ pub struct VersionIndex {
     pub compliance_level: u8,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum VersionType {
     #[default]
@@ -38,3 +40,14 @@ pub enum VersionType {
     OldAlpha,
     OldBeta,
 }
+
+impl fmt::Display for VersionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Release => "release",
+            Self::Snapshot => "snapshot",
+            Self::OldAlpha => "old_alpha",
+            Self::OldBeta => "old_beta",
+        })
+    }
+}
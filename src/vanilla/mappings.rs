@@ -0,0 +1,349 @@
+use std::{collections::HashMap, sync::LazyLock};
+
+use regex::{Captures, Regex};
+
+use crate::{download::download_bytes, Error, Result};
+
+use super::{DownloadType, VersionInfo};
+
+static CLASS_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(\S+) -> (\S+):$").unwrap());
+static FIELD_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s+(\S+) (\S+) -> (\S+)$").unwrap());
+static METHOD_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s+(?:(\d+):(\d+):)?(\S+) ([^\s(]+)\(([^)]*)\)(?::\d+:\d+)? -> (\S+)$").unwrap()
+});
+static FRAME_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"at ([\w$.]+)\.([\w$<>]+)\(([^:)]*)(?::(\d+))?\)").unwrap());
+static EXCEPTION_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^((?:Caused by: )?)([\w$.]+)(:|$)").unwrap());
+
+/// A class in proguard mappings with its fields and methods
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ClassMapping {
+    pub original: String,
+    pub obfuscated: String,
+    pub fields: Vec<FieldMapping>,
+    pub methods: Vec<MethodMapping>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldMapping {
+    pub field_type: String,
+    pub original: String,
+    pub obfuscated: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MethodMapping {
+    pub return_type: String,
+    pub original: String,
+    /// Comma separated argument types
+    pub arguments: String,
+    pub obfuscated: String,
+    /// Range of line numbers of the obfuscated method
+    pub lines: Option<(u32, u32)>,
+}
+
+/// Mojang's proguard mappings (`client_mappings`/`server_mappings`)
+/// with lookups from original to obfuscated names and back
+#[derive(Debug, Clone, Default)]
+pub struct Mappings {
+    pub classes: Vec<ClassMapping>,
+    by_original: HashMap<String, usize>,
+    by_obfuscated: HashMap<String, usize>,
+}
+
+impl Mappings {
+    /// Parses mappings, lines that aren't understood are skipped
+    #[must_use]
+    pub fn parse(text: &str) -> Self {
+        let mut classes: Vec<ClassMapping> = vec![];
+
+        for line in text.lines() {
+            if line.trim_start().starts_with('#') {
+                continue;
+            }
+
+            if let Some(caps) = CLASS_REGEX.captures(line) {
+                classes.push(ClassMapping {
+                    original: caps[1].to_owned(),
+                    obfuscated: caps[2].to_owned(),
+                    ..Default::default()
+                });
+            } else if let Some(class) = classes.last_mut() {
+                if let Some(caps) = METHOD_REGEX.captures(line) {
+                    let line_number = |i| caps.get(i).and_then(|m| m.as_str().parse().ok());
+                    class.methods.push(MethodMapping {
+                        return_type: caps[3].to_owned(),
+                        original: caps[4].to_owned(),
+                        arguments: caps[5].to_owned(),
+                        obfuscated: caps[6].to_owned(),
+                        lines: line_number(1).zip(line_number(2)),
+                    });
+                } else if let Some(caps) = FIELD_REGEX.captures(line) {
+                    class.fields.push(FieldMapping {
+                        field_type: caps[1].to_owned(),
+                        original: caps[2].to_owned(),
+                        obfuscated: caps[3].to_owned(),
+                    });
+                }
+            }
+        }
+
+        let by_original = classes
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (c.original.clone(), i))
+            .collect();
+        let by_obfuscated = classes
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (c.obfuscated.clone(), i))
+            .collect();
+
+        Self {
+            classes,
+            by_original,
+            by_obfuscated,
+        }
+    }
+
+    /// Find a class by its original name, like `net.minecraft.server.MinecraftServer`
+    #[must_use]
+    pub fn class(&self, original: &str) -> Option<&ClassMapping> {
+        self.by_original.get(original).map(|&i| &self.classes[i])
+    }
+
+    /// Find a class by its obfuscated name
+    #[must_use]
+    pub fn obfuscated_class(&self, obfuscated: &str) -> Option<&ClassMapping> {
+        self.by_obfuscated
+            .get(obfuscated)
+            .map(|&i| &self.classes[i])
+    }
+
+    #[must_use]
+    pub fn deobfuscate_class(&self, obfuscated: &str) -> Option<&str> {
+        Some(&self.obfuscated_class(obfuscated)?.original)
+    }
+
+    #[must_use]
+    pub fn obfuscate_class(&self, original: &str) -> Option<&str> {
+        Some(&self.class(original)?.obfuscated)
+    }
+
+    /// Original name of a field, by the obfuscated names of its class and itself
+    #[must_use]
+    pub fn deobfuscate_field(&self, class: &str, field: &str) -> Option<&str> {
+        self.obfuscated_class(class)?
+            .fields
+            .iter()
+            .find(|f| f.obfuscated == field)
+            .map(|f| f.original.as_str())
+    }
+
+    /// Obfuscated name of a field, by the original names of its class and itself
+    #[must_use]
+    pub fn obfuscate_field(&self, class: &str, field: &str) -> Option<&str> {
+        self.class(class)?
+            .fields
+            .iter()
+            .find(|f| f.original == field)
+            .map(|f| f.obfuscated.as_str())
+    }
+
+    /// Original name of a method, by the obfuscated names of its class and itself.
+    /// Overloads can share an obfuscated name, a line number from a stack trace picks the right one
+    #[must_use]
+    pub fn deobfuscate_method(&self, class: &str, method: &str, line: Option<u32>) -> Option<&str> {
+        let mut candidates = self
+            .obfuscated_class(class)?
+            .methods
+            .iter()
+            .filter(|m| m.obfuscated == method);
+
+        let in_range = |m: &&MethodMapping| {
+            line.zip(m.lines)
+                .is_some_and(|(line, (start, end))| (start..=end).contains(&line))
+        };
+
+        candidates
+            .clone()
+            .find(in_range)
+            .or_else(|| candidates.next())
+            .map(|m| m.original.as_str())
+    }
+
+    /// Obfuscated name of a method, by the original names of its class and itself
+    #[must_use]
+    pub fn obfuscate_method(&self, class: &str, method: &str) -> Option<&str> {
+        self.class(class)?
+            .methods
+            .iter()
+            .find(|m| m.original == method)
+            .map(|m| m.obfuscated.as_str())
+    }
+
+    /// Replaces obfuscated class and method names in a stack trace, like one from a crash report.
+    /// Exception classes are only replaced on `Caused by:` lines and lines followed by a frame,
+    /// so other lines like `Time: ...` are left alone
+    #[must_use]
+    pub fn deobfuscate_stack_trace(&self, trace: &str) -> String {
+        let trace = FRAME_REGEX.replace_all(trace, |caps: &Captures| {
+            let class = &caps[1];
+            let line = caps.get(4).and_then(|m| m.as_str().parse().ok());
+            let method = self
+                .deobfuscate_method(class, &caps[2], line)
+                .unwrap_or(&caps[2]);
+            let source = caps.get(4).map_or_else(
+                || caps[3].to_owned(),
+                |line| format!("{}:{}", &caps[3], line.as_str()),
+            );
+
+            format!(
+                "at {}.{method}({source})",
+                self.deobfuscate_class(class).unwrap_or(class)
+            )
+        });
+
+        let lines: Vec<&str> = trace.split_inclusive('\n').collect();
+        let mut out = String::with_capacity(trace.len());
+        for (i, line) in lines.iter().enumerate() {
+            let is_header = line.starts_with("Caused by: ")
+                || lines
+                    .get(i + 1)
+                    .is_some_and(|next| next.trim_start().starts_with("at "));
+            if !is_header {
+                out.push_str(line);
+                continue;
+            }
+
+            let content = line.trim_end_matches(['\r', '\n']);
+            let header = EXCEPTION_REGEX.replace(content, |caps: &Captures| {
+                format!(
+                    "{}{}{}",
+                    &caps[1],
+                    self.deobfuscate_class(&caps[2]).unwrap_or(&caps[2]),
+                    &caps[3]
+                )
+            });
+            out.push_str(&header);
+            out.push_str(&line[content.len()..]);
+        }
+
+        out
+    }
+}
+
+impl VersionInfo {
+    /// Fetch and parse the mappings of this version,
+    /// `download_type` should be [`DownloadType::ClientMappings`] or [`DownloadType::ServerMappings`]
+    pub async fn fetch_mappings(
+        &self,
+        client: &reqwest::Client,
+        download_type: &DownloadType,
+    ) -> Result<Mappings> {
+        let file = self
+            .downloads
+            .get(download_type)
+            .ok_or_else(|| Error::NotFound(format!("{download_type:?} of {}", self.id)))?;
+        let data = download_bytes(client, &file.url, &file.sha1, file.size).await?;
+
+        Ok(Mappings::parse(&String::from_utf8_lossy(&data)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAPPINGS: &str = r#"# {"id":"com.android.tools.r8.mapping","version":"1.0"}
+net.minecraft.ReportedException -> z:
+# {"fileName":"ReportedException.java","id":"sourceFile"}
+    net.minecraft.CrashReport report -> a
+    1:1:void <init>(net.minecraft.CrashReport):0:0 -> <init>
+net.minecraft.server.MinecraftServer -> net.minecraft.server.MinecraftServer:
+    org.slf4j.Logger LOGGER -> k
+    int tickCount -> ah
+    97:97:void tickServer(java.util.function.BooleanSupplier) -> b
+net.minecraft.world.level.Level -> dcw:
+    boolean isClientSide -> y
+    210:212:boolean setBlock(net.minecraft.core.BlockPos,net.minecraft.world.level.block.state.BlockState,int) -> a
+    218:263:boolean removeBlock(net.minecraft.core.BlockPos,boolean) -> a
+"#;
+
+    #[test]
+    fn parses_proguard_mappings() {
+        let mappings = Mappings::parse(MAPPINGS);
+        assert_eq!(mappings.classes.len(), 3);
+
+        let level = mappings.class("net.minecraft.world.level.Level").unwrap();
+        assert_eq!(level.obfuscated, "dcw");
+        assert_eq!(
+            level.fields,
+            [FieldMapping {
+                field_type: "boolean".to_owned(),
+                original: "isClientSide".to_owned(),
+                obfuscated: "y".to_owned(),
+            }]
+        );
+        assert_eq!(
+            level.methods[1].arguments,
+            "net.minecraft.core.BlockPos,boolean"
+        );
+        assert_eq!(level.methods[1].lines, Some((218, 263)));
+
+        assert_eq!(
+            mappings.deobfuscate_class("z"),
+            Some("net.minecraft.ReportedException")
+        );
+        assert_eq!(mappings.deobfuscate_field("dcw", "y"), Some("isClientSide"));
+        assert_eq!(
+            mappings.obfuscate_field("net.minecraft.server.MinecraftServer", "tickCount"),
+            Some("ah")
+        );
+        assert_eq!(
+            mappings.deobfuscate_method("dcw", "a", Some(211)),
+            Some("setBlock")
+        );
+        assert_eq!(
+            mappings.deobfuscate_method("dcw", "a", Some(240)),
+            Some("removeBlock")
+        );
+        assert_eq!(
+            mappings.deobfuscate_method("dcw", "a", None),
+            Some("setBlock")
+        );
+    }
+
+    #[test]
+    fn deobfuscates_stack_traces() {
+        let mappings = Mappings::parse(MAPPINGS);
+        let trace = "---- Minecraft Crash Report ----\n\
+            Time: 2024-01-01 12:00:00\n\
+            z: Exception ticking world\n\
+            \tat dcw.a(SourceFile:240)\n\
+            \tat net.minecraft.server.MinecraftServer.b(SourceFile:97)\n\
+            Caused by: z: boom\n\
+            \tat dcw.a(SourceFile:211)\n";
+
+        assert_eq!(
+            mappings.deobfuscate_stack_trace(trace),
+            "---- Minecraft Crash Report ----\n\
+            Time: 2024-01-01 12:00:00\n\
+            net.minecraft.ReportedException: Exception ticking world\n\
+            \tat net.minecraft.world.level.Level.removeBlock(SourceFile:240)\n\
+            \tat net.minecraft.server.MinecraftServer.tickServer(SourceFile:97)\n\
+            Caused by: net.minecraft.ReportedException: boom\n\
+            \tat net.minecraft.world.level.Level.setBlock(SourceFile:211)\n"
+        );
+    }
+
+    #[test]
+    fn leaves_other_lines_alone() {
+        // short obfuscated names can collide with words in a crash report
+        let mappings = Mappings::parse("net.minecraft.util.Clock -> Time:\n");
+        let report = "Time: 2024-01-01 12:00:00\nDescription: Ticking entity\n";
+        assert_eq!(mappings.deobfuscate_stack_trace(report), report);
+    }
+}
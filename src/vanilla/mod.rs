@@ -1,17 +1,42 @@
 //! API implementation of piston-meta (mojang's launcher api)
 //! Contains implementations for fetching versions, downloading, libraries and assets
 
-use std::{collections::HashMap, env};
+use std::{
+    collections::HashMap,
+    env,
+    path::{Path, PathBuf},
+    sync::LazyLock,
+};
 
 use regex::Regex;
 
-use crate::{dollar_repl, Error, Result};
+use crate::{
+    dollar_repl,
+    download::{ensure_file, FileStatus},
+    java::{get_java_requirement, JavaPlatform, JavaRequirement},
+    Error, MCVersion, Result,
+};
 
 mod assets;
+mod bundler;
+mod install;
+mod launch;
+mod log4shell;
 mod manifest;
+mod mappings;
+mod natives;
+mod profile;
+mod protocol;
+mod query;
+pub mod runtime;
+mod timeline;
 mod version;
+mod watch;
 
-pub use crate::vanilla::{assets::*, manifest::*, version::*};
+pub use crate::vanilla::{
+    assets::*, bundler::*, install::*, launch::*, log4shell::*, manifest::*, mappings::*,
+    natives::*, protocol::*, query::*, timeline::*, version::*, watch::*,
+};
 
 pub const VERSION_MANIFEST_URL: &str =
     "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json";
@@ -36,6 +61,12 @@ impl VersionManifest {
         self.versions.iter().find(|v| v.id == id).cloned()
     }
 
+    /// Build a [`VersionTimeline`] for chronological comparisons and queries
+    #[must_use]
+    pub fn timeline(&self) -> VersionTimeline<'_> {
+        VersionTimeline::new(self)
+    }
+
     /// Fetch the latest release's `VersionInfo`
     pub async fn fetch_latest_release(&self, client: &reqwest::Client) -> Result<VersionInfo> {
         let id = self.latest.release.clone();
@@ -71,6 +102,62 @@ impl VersionIndex {
 }
 
 impl VersionInfo {
+    /// Java requirement of this version on `platform`.
+    /// The version's own `java_version` takes priority over the built-in table
+    #[must_use]
+    pub fn java_requirement(&self, platform: JavaPlatform) -> JavaRequirement {
+        // parsing only fails for an empty id
+        let version = self
+            .id
+            .parse()
+            .unwrap_or_else(|_| MCVersion::Special(self.id.clone()));
+        let mut requirement = get_java_requirement(platform, &version);
+
+        if self.java_version.major_version != 0 {
+            requirement.required = requirement.required.max(self.java_version.major_version);
+        }
+
+        requirement
+    }
+
+    /// Id of the asset index, `assets` for versions without one
+    #[must_use]
+    pub fn asset_index_id(&self) -> &str {
+        self.asset_index.id.as_ref().unwrap_or(&self.assets)
+    }
+
+    /// Id of the version whose client jar this version runs
+    #[must_use]
+    pub fn jar_id(&self) -> &str {
+        self.jar.as_ref().unwrap_or(&self.id)
+    }
+
+    /// The version's arguments in the modern format.
+    /// Versions before 1.13 only have a space-separated `minecraft_arguments`,
+    /// for those the game arguments are split from it and the jvm arguments
+    /// the launcher used to add are filled in
+    #[must_use]
+    pub fn resolved_arguments(&self) -> VersionArguments {
+        let mut arguments = self.arguments.clone();
+        if self.minecraft_arguments.is_empty() {
+            return arguments;
+        }
+
+        if arguments.game.is_empty() {
+            arguments.game = self
+                .minecraft_arguments
+                .split_whitespace()
+                .map(|arg| PistonArgument::Normal(arg.to_owned()))
+                .collect();
+        }
+
+        if arguments.jvm.is_empty() {
+            arguments.jvm = legacy_jvm_arguments();
+        }
+
+        arguments
+    }
+
     pub async fn fetch_asset_index(&self, client: &reqwest::Client) -> Result<MCAssetIndex> {
         Ok(client
             .get(&self.asset_index.url)
@@ -82,10 +169,60 @@ impl VersionInfo {
     }
 }
 
+static WINDOWS_10_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^10\.").unwrap());
+
+/// Default jvm arguments of the launcher for versions without `arguments.jvm`
+fn legacy_jvm_arguments() -> Vec<PistonArgument> {
+    let os = |name: &str, version: Option<&Regex>| {
+        vec![PistonRule::Allow(PistonRuleConstraints {
+            os: Some(PistonOsConstraint {
+                name: name.to_owned(),
+                arch: String::new(),
+                version: version.map(|v| OsVersionRegex(v.clone())),
+            }),
+            features: None,
+        })]
+    };
+
+    let mut args = vec![
+        PistonArgument::Ruled {
+            rules: os("osx", None),
+            value: ArgumentValue::Single("-XstartOnFirstThread".to_owned()),
+        },
+        PistonArgument::Ruled {
+            rules: os("windows", None),
+            value: ArgumentValue::Single(
+                "-XX:HeapDumpPath=MojangTricksIntelDriversForPerformance_javaw.exe_minecraft.exe.heapdump"
+                    .to_owned(),
+            ),
+        },
+        PistonArgument::Ruled {
+            rules: os("windows", Some(&WINDOWS_10_REGEX)),
+            value: ArgumentValue::Many(vec![
+                "-Dos.name=Windows 10".to_owned(),
+                "-Dos.version=10.0".to_owned(),
+            ]),
+        },
+    ];
+
+    args.extend(
+        [
+            "-Djava.library.path=${natives_directory}",
+            "-Dminecraft.launcher.brand=${launcher_name}",
+            "-Dminecraft.launcher.version=${launcher_version}",
+            "-cp",
+            "${classpath}",
+        ]
+        .map(|arg| PistonArgument::Normal(arg.to_owned())),
+    );
+
+    args
+}
+
 /// `PistonRuleMatcher` is an utility for matching argument and library rules
 pub struct PistonRuleMatcher {
     pub os: PistonOs,
-    pub features: HashMap<String, bool>,
+    pub features: LaunchFeatures,
 }
 
 impl PistonRuleMatcher {
@@ -98,7 +235,7 @@ impl PistonRuleMatcher {
                 arch: os_arch,
                 version: os_version,
             },
-            features: HashMap::new(),
+            features: LaunchFeatures::default(),
         }
     }
 
@@ -111,7 +248,7 @@ impl PistonRuleMatcher {
                 arch: String::new(),
                 version: String::new(),
             },
-            features: HashMap::new(),
+            features: LaunchFeatures::default(),
         }
     }
 
@@ -138,7 +275,7 @@ impl PistonRuleMatcher {
                 arch: env::consts::ARCH.to_owned(),
                 version: os_version,
             },
-            features: HashMap::new(),
+            features: LaunchFeatures::default(),
         }
     }
 
@@ -147,45 +284,63 @@ impl PistonRuleMatcher {
     }
 
     /// find classifier from library.
-    /// Some(PistonFile) if classifier for matcher exists
-    /// None if no classifiers exist/no matches
-    #[must_use]
-    pub fn get_native_library(&self, library: &PistonLibrary) -> Option<PistonFile> {
-        if let Some(native_keys) = &library.natives {
-            if let Some(classifier_key) = native_keys.get(&self.os.name) {
-                if let Some(map) = &library.downloads.classifiers {
-                    return Some(
-                        map[&self.process_string(&HashMap::new(), classifier_key)].clone(),
-                    );
-                }
-            }
-        }
+    /// Ok(Some(PistonFile)) if classifier for matcher exists
+    /// Ok(None) if the library has no natives for this os
+    /// Err(NotFound) if the classifier it names isn't in the downloads
+    pub fn get_native_library(&self, library: &PistonLibrary) -> Result<Option<PistonFile>> {
+        let Some(classifier_key) = library
+            .natives
+            .as_ref()
+            .and_then(|natives| natives.get(&self.os.name))
+        else {
+            return Ok(None);
+        };
 
-        None
+        // like the launcher, `${arch}` in classifiers is the jvm's bitness rather than the cpu
+        let classifier = dollar_repl(classifier_key, |key| {
+            (key == "arch").then(|| self.arch_bits().to_owned())
+        });
+        library
+            .get_native(&classifier)
+            .map(Some)
+            .ok_or_else(|| Error::NotFound(format!("{classifier} of {}", library.name)))
     }
 
-    pub fn match_rules(&self, rules: &Vec<PistonRule>) -> Result<bool> {
+    /// `64` or `32`, assumes 64-bit unless the arch is known to be 32-bit
+    fn arch_bits(&self) -> &'static str {
+        match self.os.arch.as_str() {
+            "x86" | "i386" | "i586" | "i686" | "arm" | "armv7" => "32",
+            _ => "64",
+        }
+    }
+
+    /// Whether rules allow something, like the launcher does:
+    /// without rules it's allowed, otherwise it starts disallowed
+    /// and every rule whose constraints match sets the action, so the last matching rule wins
+    pub fn match_rules(&self, rules: &[PistonRule]) -> Result<bool> {
         if rules.is_empty() {
             return Ok(true);
         }
 
+        let mut allowed = false;
         for rule in rules {
-            if !self.match_rule(rule)? {
-                return Ok(false);
+            if let Some(allow) = self.match_rule(rule)? {
+                allowed = allow;
             }
         }
 
-        Ok(true)
+        Ok(allowed)
     }
 
-    pub fn match_rule(&self, rule: &PistonRule) -> Result<bool> {
-        Ok(match rule {
-            PistonRule::Allow(constraint) => self.match_constraint(constraint)?,
-            PistonRule::Disallow(constraint) => {
-                // Fuck it
-                !self.match_constraint(constraint)?
-            }
-        })
+    /// `Some(true)` for an allow rule and `Some(false)` for a disallow rule if its constraints match,
+    /// `None` if the rule doesn't apply
+    pub fn match_rule(&self, rule: &PistonRule) -> Result<Option<bool>> {
+        let (allow, constraint) = match rule {
+            PistonRule::Allow(constraint) => (true, constraint),
+            PistonRule::Disallow(constraint) => (false, constraint),
+        };
+
+        Ok(self.match_constraint(constraint)?.then_some(allow))
     }
 
     pub fn match_constraint(&self, constraint: &PistonRuleConstraints) -> Result<bool> {
@@ -198,14 +353,16 @@ impl PistonRuleMatcher {
                 return Ok(false);
             }
 
-            if !os.version.is_empty() && !Regex::new(&os.version)?.is_match(&self.os.version) {
-                return Ok(false);
+            if let Some(version) = &os.version {
+                if !version.0.is_match(&self.os.version) {
+                    return Ok(false);
+                }
             }
         }
 
         if let Some(feats) = &constraint.features {
-            for feat in feats.keys() {
-                if !self.features.contains_key(feat) || !self.features[feat] {
+            for (feat, state) in feats {
+                if self.features.is_enabled(feat) != *state {
                     return Ok(false);
                 }
             }
@@ -229,9 +386,9 @@ impl PistonRuleMatcher {
                             ArgumentValue::Single(v) => list.push(v.to_owned()),
                             // bad
                             ArgumentValue::Many(li) => {
-                                li.iter().for_each(|v| list.push(v.to_owned()));
+                                list.extend(li.iter().cloned());
                             }
-                        };
+                        }
                     }
                 }
             }
@@ -240,6 +397,19 @@ impl PistonRuleMatcher {
         Ok(list.iter().map(|s| self.process_string(map, s)).collect())
     }
 
+    /// Builds the jvm and game arguments of a version, see [`VersionInfo::resolved_arguments()`]
+    pub fn build_version_args(
+        &self,
+        version: &VersionInfo,
+        map: &HashMap<String, String>,
+    ) -> Result<(Vec<String>, Vec<String>)> {
+        let arguments = version.resolved_arguments();
+        Ok((
+            self.build_args(&arguments.jvm, map)?,
+            self.build_args(&arguments.game, map)?,
+        ))
+    }
+
     #[must_use]
     pub fn process_string(&self, map: &HashMap<String, String>, input: &str) -> String {
         dollar_repl(input, |key| {
@@ -252,6 +422,28 @@ impl PistonRuleMatcher {
     }
 }
 
+impl VersionLoggingInfo {
+    /// Where the config is stored, `assets/log_configs/<id>` like the vanilla launcher
+    #[must_use]
+    pub fn path_in(&self, assets_directory: &Path) -> PathBuf {
+        let name = self
+            .file
+            .id
+            .clone()
+            .or_else(|| self.file.url.rsplit('/').next().map(ToOwned::to_owned))
+            .unwrap_or_default();
+        assets_directory.join("log_configs").join(name)
+    }
+
+    /// The jvm argument with `${path}` pointing at the config
+    #[must_use]
+    pub fn argument_for(&self, config: &Path) -> String {
+        dollar_repl(&self.argument, |key| {
+            (key == "path").then(|| config.to_string_lossy().into_owned())
+        })
+    }
+}
+
 impl PistonLibrary {
     pub async fn download_artifact(&self, client: &reqwest::Client) -> Result<reqwest::Response> {
         self.downloads.download_artifact(client).await
@@ -279,9 +471,62 @@ impl PistonLibrary {
         }
     }
 
+    /// Path of the artifact in the libraries directory.
+    /// Libraries from a maven repository (`url`) use their maven path
     #[must_use]
     pub fn get_artifact_path(&self) -> Option<String> {
-        self.downloads.artifact.path.as_ref().cloned()
+        self.downloads
+            .artifact
+            .path
+            .clone()
+            .or_else(|| self.url.as_ref().and_then(|_| self.maven_path()))
+    }
+
+    /// Download url of the artifact, from `downloads` or the maven repository
+    #[must_use]
+    pub fn get_artifact_url(&self) -> Option<String> {
+        if !self.downloads.artifact.url.is_empty() {
+            return Some(self.downloads.artifact.url.clone());
+        }
+
+        let repository = self.url.as_ref()?;
+        Some(format!(
+            "{}/{}",
+            repository.trim_end_matches('/'),
+            self.maven_path()?
+        ))
+    }
+
+    /// Path of the library in a maven repository, from its `group:artifact:version[:classifier][@ext]` name
+    #[must_use]
+    pub fn maven_path(&self) -> Option<String> {
+        let (name, extension) = self.name.split_once('@').unwrap_or((&self.name, "jar"));
+        let mut parts = name.split(':');
+        let group = parts.next()?;
+        let artifact = parts.next()?;
+        let version = parts.next()?;
+        let classifier = parts.next().map(|c| format!("-{c}")).unwrap_or_default();
+
+        Some(format!(
+            "{}/{artifact}/{version}/{artifact}-{version}{classifier}.{extension}",
+            group.replace('.', "/")
+        ))
+    }
+
+    /// `group:artifact[:classifier]`, the name without the version.
+    /// Two libraries with the same key are different versions of one library
+    #[must_use]
+    pub fn library_key(&self) -> String {
+        let name = self
+            .name
+            .split_once('@')
+            .map_or(self.name.as_str(), |(n, _)| n);
+        let parts: Vec<&str> = name.split(':').collect();
+        match parts.as_slice() {
+            [group, artifact, _, classifier, ..] => format!("{group}:{artifact}:{classifier}"),
+            [group, artifact, ..] => format!("{group}:{artifact}"),
+            _ => name.to_owned(),
+        }
     }
 
     #[must_use]
@@ -315,4 +560,63 @@ impl PistonFile {
     pub async fn download(&self, client: &reqwest::Client) -> Result<reqwest::Response> {
         Ok(client.get(&self.url).send().await?.error_for_status()?)
     }
+
+    /// Download to `path` verifying the sha1 and size, unless a valid copy is already there
+    pub async fn download_to(&self, client: &reqwest::Client, path: &Path) -> Result<FileStatus> {
+        ensure_file(client, &self.url, path, &self.sha1, self.size).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(json: serde_json::Value) -> Vec<PistonRule> {
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn matcher(os: &str, version: &str) -> PistonRuleMatcher {
+        PistonRuleMatcher::new(os.to_owned(), "x86_64".to_owned(), version.to_owned())
+    }
+
+    #[test]
+    fn last_matching_rule_wins() {
+        let rules = rules(serde_json::json!([
+            { "action": "allow" },
+            { "action": "disallow", "os": { "name": "osx" } },
+        ]));
+
+        assert!(!matcher("osx", "").match_rules(&rules).unwrap());
+        assert!(matcher("linux", "").match_rules(&rules).unwrap());
+    }
+
+    #[test]
+    fn empty_rules_allow() {
+        assert!(PistonRuleMatcher::empty().match_rules(&[]).unwrap());
+    }
+
+    #[test]
+    fn feature_required_false() {
+        let rules = rules(serde_json::json!([
+            { "action": "allow", "features": { "is_demo_user": false } },
+        ]));
+
+        let mut matcher = matcher("linux", "");
+        assert!(matcher.match_rules(&rules).unwrap());
+        matcher.features.is_demo_user = true;
+        assert!(!matcher.match_rules(&rules).unwrap());
+    }
+
+    #[test]
+    fn os_version_regex() {
+        let rules = rules(serde_json::json!([
+            { "action": "allow", "os": { "name": "windows", "version": "^10\\." } },
+        ]));
+
+        assert!(matcher("windows", "10.0.19045")
+            .match_rules(&rules)
+            .unwrap());
+        assert!(!matcher("windows", "6.1.7601").match_rules(&rules).unwrap());
+        assert!(!matcher("linux", "10.0").match_rules(&rules).unwrap());
+    }
 }
@@ -0,0 +1,43 @@
+use std::{
+    fs::{self, File},
+    io,
+    path::Path,
+};
+
+use zip::ZipArchive;
+
+use crate::Result;
+
+/// Extracts a native library jar into `directory`.
+/// Entries starting with one of the `exclude` prefixes (like `META-INF/`) are skipped,
+/// and so are entries that would end up outside `directory`
+pub fn extract_natives(jar: &Path, directory: &Path, exclude: &[String]) -> Result<()> {
+    let mut archive = ZipArchive::new(File::open(jar)?)?;
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        if exclude
+            .iter()
+            .any(|prefix| entry.name().starts_with(prefix))
+        {
+            continue;
+        }
+
+        let Some(relative) = entry.enclosed_name().map(Path::to_owned) else {
+            continue;
+        };
+        let target = directory.join(relative);
+
+        if entry.is_dir() {
+            fs::create_dir_all(&target)?;
+            continue;
+        }
+
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        io::copy(&mut entry, &mut File::create(&target)?)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,226 @@
+use std::collections::HashSet;
+
+use crate::{Error, Result};
+
+use super::{PistonLibrary, VersionInfo, VersionManifest};
+
+/// Profiles can inherit from profiles that inherit, but not forever
+const MAX_INHERITANCE_DEPTH: usize = 8;
+
+impl VersionInfo {
+    /// Merges this profile with the profile it `inherits_from`.
+    ///
+    /// - libraries of this profile come first, and replace the parent's
+    ///   libraries with the same `group:artifact[:classifier]`
+    /// - arguments are the parent's followed by this profile's
+    /// - `main_class`, `minecraft_arguments`, `java_version` and other values
+    ///   are taken from this profile when set, otherwise from the parent
+    #[must_use]
+    pub fn inherit(&self, parent: &VersionInfo) -> VersionInfo {
+        let keys: HashSet<String> = self
+            .libraries
+            .iter()
+            .map(PistonLibrary::library_key)
+            .collect();
+        let mut libraries = self.libraries.clone();
+        libraries.extend(
+            parent
+                .libraries
+                .iter()
+                .filter(|l| !keys.contains(&l.library_key()))
+                .cloned(),
+        );
+
+        let mut arguments = parent.resolved_arguments();
+        if !self.minecraft_arguments.is_empty() && self.arguments.game.is_empty() {
+            // a legacy child replaces the parent's game arguments entirely
+            arguments.game = self.resolved_arguments().game;
+        } else {
+            arguments.game.extend(self.arguments.game.iter().cloned());
+        }
+        arguments.jvm.extend(self.arguments.jvm.iter().cloned());
+
+        let mut downloads = parent.downloads.clone();
+        downloads.extend(self.downloads.clone());
+
+        let or = |child: &String, parent: &String| {
+            if child.is_empty() {
+                parent.clone()
+            } else {
+                child.clone()
+            }
+        };
+
+        VersionInfo {
+            id: self.id.clone(),
+            inherits_from: parent.inherits_from.clone(),
+            jar: self
+                .jar
+                .clone()
+                .or_else(|| parent.jar.clone())
+                .or_else(|| Some(parent.id.clone())),
+            assets: or(&self.assets, &parent.assets),
+            asset_index: if self.asset_index.url.is_empty() {
+                parent.asset_index.clone()
+            } else {
+                self.asset_index.clone()
+            },
+            java_version: if self.java_version.major_version == 0 {
+                parent.java_version.clone()
+            } else {
+                self.java_version.clone()
+            },
+            libraries,
+            downloads,
+            arguments,
+            // already resolved into `arguments`
+            minecraft_arguments: String::new(),
+            compliance_level: self.compliance_level.max(parent.compliance_level),
+            minimum_launcher_version: self
+                .minimum_launcher_version
+                .max(parent.minimum_launcher_version),
+            main_class: or(&self.main_class, &parent.main_class),
+            logging: if self.logging.client.is_none() {
+                parent.logging.clone()
+            } else {
+                self.logging.clone()
+            },
+            version_type: self.version_type,
+            time: or(&self.time, &parent.time),
+            release_time: or(&self.release_time, &parent.release_time),
+        }
+    }
+}
+
+impl VersionManifest {
+    /// Resolves `inherits_from` of a profile by fetching its parents from the manifest,
+    /// see [`VersionInfo::inherit()`]
+    pub async fn resolve_inheritance(
+        &self,
+        mut version: VersionInfo,
+        client: &reqwest::Client,
+    ) -> Result<VersionInfo> {
+        for _ in 0..MAX_INHERITANCE_DEPTH {
+            let Some(parent_id) = version.inherits_from.clone() else {
+                return Ok(version);
+            };
+
+            let parent = self.fetch(&parent_id, client).await?;
+            version = version.inherit(&parent);
+        }
+
+        Err(Error::NotFound(format!(
+            "root profile of {} within {MAX_INHERITANCE_DEPTH} parents",
+            version.id
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vanilla::PistonArgument;
+
+    fn profile(json: serde_json::Value) -> VersionInfo {
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn parent() -> VersionInfo {
+        profile(serde_json::json!({
+            "id": "1.20.4",
+            "mainClass": "net.minecraft.client.main.Main",
+            "libraries": [
+                { "name": "org.ow2.asm:asm:9.3" },
+                { "name": "org.joml:joml:1.10.5" },
+            ],
+            "arguments": {
+                "game": ["--username", "${auth_player_name}"],
+                "jvm": ["-cp", "${classpath}"],
+            },
+        }))
+    }
+
+    fn game_args(version: &VersionInfo) -> Vec<&str> {
+        version
+            .arguments
+            .game
+            .iter()
+            .filter_map(|arg| match arg {
+                PistonArgument::Normal(arg) => Some(arg.as_str()),
+                PistonArgument::Ruled { .. } => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn child_libraries_replace_parents() {
+        let child = profile(serde_json::json!({
+            "id": "fabric-loader-0.15.7-1.20.4",
+            "inheritsFrom": "1.20.4",
+            "mainClass": "net.fabricmc.loader.impl.launch.knot.KnotClient",
+            "libraries": [
+                { "name": "org.ow2.asm:asm:9.6" },
+                { "name": "net.fabricmc:fabric-loader:0.15.7" },
+            ],
+            "arguments": { "game": ["--fabric"] },
+        }));
+
+        let merged = child.inherit(&parent());
+        let libraries: Vec<&str> = merged.libraries.iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(
+            libraries,
+            [
+                "org.ow2.asm:asm:9.6",
+                "net.fabricmc:fabric-loader:0.15.7",
+                "org.joml:joml:1.10.5",
+            ]
+        );
+        assert_eq!(
+            game_args(&merged),
+            ["--username", "${auth_player_name}", "--fabric"]
+        );
+        assert_eq!(
+            merged.main_class,
+            "net.fabricmc.loader.impl.launch.knot.KnotClient"
+        );
+        assert_eq!(merged.inherits_from, None);
+        assert_eq!(merged.jar.as_deref(), Some("1.20.4"));
+    }
+
+    #[test]
+    fn legacy_child_replaces_game_arguments() {
+        let child = profile(serde_json::json!({
+            "id": "1.20.4-forge",
+            "inheritsFrom": "1.20.4",
+            "minecraftArguments": "--username ${auth_player_name} --tweakClass cpw.mods.fml.common.launcher.FMLTweaker",
+        }));
+
+        let merged = child.inherit(&parent());
+        assert_eq!(
+            game_args(&merged),
+            [
+                "--username",
+                "${auth_player_name}",
+                "--tweakClass",
+                "cpw.mods.fml.common.launcher.FMLTweaker",
+            ]
+        );
+        assert!(merged.minecraft_arguments.is_empty());
+        assert_eq!(merged.main_class, "net.minecraft.client.main.Main");
+    }
+
+    #[test]
+    fn jar_falls_back_to_parent() {
+        let child = profile(serde_json::json!({ "id": "child", "inheritsFrom": "1.20.4" }));
+        let merged = child.inherit(&parent());
+        assert_eq!(merged.jar.as_deref(), Some("1.20.4"));
+        assert_eq!(merged.jar_id(), "1.20.4");
+
+        let child = profile(serde_json::json!({
+            "id": "child",
+            "inheritsFrom": "1.20.4",
+            "jar": "custom",
+        }));
+        assert_eq!(child.inherit(&parent()).jar_id(), "custom");
+    }
+}
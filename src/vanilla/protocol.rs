@@ -0,0 +1,123 @@
+use std::sync::LazyLock;
+
+use serde::{Deserialize, Serialize};
+
+use crate::MCVersion;
+
+/// Numbers identifying a release's network protocol, world format and pack formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct VersionProtocolInfo {
+    /// Network protocol version, as sent in handshakes and ping responses
+    pub protocol: u32,
+    /// `DataVersion` of worlds, chunks and player data. Added in 1.9
+    pub data_version: Option<u32>,
+    /// `pack_format` of resource packs. Added in 1.6.1
+    pub resource_pack_format: Option<u32>,
+    /// `pack_format` of data packs. Added in 1.13
+    pub data_pack_format: Option<u32>,
+}
+
+const PROTOCOL_TABLE_CSV: &str = include_str!("protocol.csv");
+
+static PROTOCOL_TABLE: LazyLock<Vec<(MCVersion, VersionProtocolInfo)>> =
+    LazyLock::new(|| parse_protocol_table(PROTOCOL_TABLE_CSV));
+
+fn parse_protocol_table(csv: &str) -> Vec<(MCVersion, VersionProtocolInfo)> {
+    let optional = |s: &str| (s != "-").then(|| s.parse().unwrap());
+
+    csv.lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let cols: Vec<&str> = line.split(',').collect();
+            (
+                cols[0].parse().unwrap(),
+                VersionProtocolInfo {
+                    protocol: cols[1].parse().unwrap(),
+                    data_version: optional(cols[2]),
+                    resource_pack_format: optional(cols[3]),
+                    data_pack_format: optional(cols[4]),
+                },
+            )
+        })
+        .collect()
+}
+
+/// Every release in the built-in table, oldest first
+pub fn protocol_table() -> impl Iterator<Item = &'static (MCVersion, VersionProtocolInfo)> {
+    PROTOCOL_TABLE.iter()
+}
+
+/// Get the protocol info of a release, if it's in the built-in table (1.6.1 and later)
+#[must_use]
+pub fn get_protocol_info(version: &MCVersion) -> Option<VersionProtocolInfo> {
+    protocol_table()
+        .find(|(v, _)| v == version)
+        .map(|(_, info)| *info)
+}
+
+/// All releases using a network protocol version, oldest first
+#[must_use]
+pub fn find_versions_by_protocol(protocol: u32) -> Vec<MCVersion> {
+    find_versions(|info| info.protocol == protocol)
+}
+
+/// The release that writes worlds with this `DataVersion`
+#[must_use]
+pub fn find_version_by_data_version(data_version: u32) -> Option<MCVersion> {
+    find_versions(|info| info.data_version == Some(data_version))
+        .into_iter()
+        .next()
+}
+
+/// All releases accepting resource packs of this `pack_format`, oldest first
+#[must_use]
+pub fn find_versions_by_resource_pack_format(format: u32) -> Vec<MCVersion> {
+    find_versions(|info| info.resource_pack_format == Some(format))
+}
+
+/// All releases accepting data packs of this `pack_format`, oldest first
+#[must_use]
+pub fn find_versions_by_data_pack_format(format: u32) -> Vec<MCVersion> {
+    find_versions(|info| info.data_pack_format == Some(format))
+}
+
+fn find_versions<F>(filter: F) -> Vec<MCVersion>
+where
+    F: Fn(&VersionProtocolInfo) -> bool,
+{
+    protocol_table()
+        .filter(|(_, info)| filter(info))
+        .map(|(v, _)| v.clone())
+        .collect()
+}
+
+impl MCVersion {
+    /// See [`get_protocol_info()`]
+    #[must_use]
+    pub fn protocol_info(&self) -> Option<VersionProtocolInfo> {
+        get_protocol_info(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embedded_table_parses() {
+        let table = parse_protocol_table(PROTOCOL_TABLE_CSV);
+        assert!(table.iter().all(|(version, _)| version.is_release()));
+        assert!(table.windows(2).all(|pair| pair[0].0 < pair[1].0));
+
+        let info = get_protocol_info(&"1.21.11".parse().unwrap()).unwrap();
+        assert_eq!(info.protocol, 774);
+        assert_eq!(info.data_version, Some(4671));
+
+        let info = get_protocol_info(&"26.1".parse().unwrap()).unwrap();
+        assert_eq!(info.protocol, 775);
+        assert_eq!(
+            find_version_by_data_version(4786),
+            Some("26.1".parse().unwrap())
+        );
+    }
+}
@@ -0,0 +1,258 @@
+use std::collections::BTreeMap;
+
+use crate::{alias::VersionAlias, Error, MCVersion, MCVersionReqSet, Result};
+
+use super::{
+    timeline::by_release_time, VersionIndex, VersionManifest, VersionTimeline, VersionType,
+};
+
+/// Conditions on versions of a [`VersionManifest`], every set condition has to match
+#[derive(Debug, Clone, Default)]
+pub struct VersionFilter {
+    /// Any type if empty
+    pub version_types: Vec<VersionType>,
+    /// Inclusive, compared with `release_time` as RFC 3339 timestamps or prefixes of one like `2023-06`
+    pub released_after: Option<String>,
+    /// Exclusive, see `released_after`
+    pub released_before: Option<String>,
+    pub max_compliance_level: Option<u8>,
+    /// Only releases can match a requirement
+    pub requirement: Option<MCVersionReqSet>,
+}
+
+impl VersionFilter {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn version_type(mut self, version_type: VersionType) -> Self {
+        self.version_types.push(version_type);
+        self
+    }
+
+    #[must_use]
+    pub fn released_after(mut self, time: &str) -> Self {
+        self.released_after = Some(time.to_owned());
+        self
+    }
+
+    #[must_use]
+    pub fn released_before(mut self, time: &str) -> Self {
+        self.released_before = Some(time.to_owned());
+        self
+    }
+
+    #[must_use]
+    pub fn max_compliance_level(mut self, level: u8) -> Self {
+        self.max_compliance_level = Some(level);
+        self
+    }
+
+    #[must_use]
+    pub fn requirement<R: Into<MCVersionReqSet>>(mut self, requirement: R) -> Self {
+        self.requirement = Some(requirement.into());
+        self
+    }
+
+    #[must_use]
+    pub fn matches(&self, version: &VersionIndex) -> bool {
+        // release times are RFC 3339 timestamps in UTC, so they compare as strings
+        let time = version.release_time.as_str();
+
+        (self.version_types.is_empty() || self.version_types.contains(&version.version_type))
+            && self
+                .released_after
+                .as_ref()
+                .is_none_or(|after| time >= after.as_str())
+            && self
+                .released_before
+                .as_ref()
+                .is_none_or(|before| time < before.as_str())
+            && self
+                .max_compliance_level
+                .is_none_or(|level| version.compliance_level <= level)
+            && self.requirement.as_ref().is_none_or(|requirement| {
+                version
+                    .id
+                    .parse::<MCVersion>()
+                    .is_ok_and(|v| requirement.matches(&v))
+            })
+    }
+}
+
+impl<'a> VersionTimeline<'a> {
+    /// Versions matching the filter, oldest first
+    #[must_use]
+    pub fn filter(&self, filter: &VersionFilter) -> Vec<&'a VersionIndex> {
+        self.iter().filter(|v| filter.matches(v)).collect()
+    }
+
+    /// The newest version matching the filter
+    #[must_use]
+    pub fn latest_matching(&self, filter: &VersionFilter) -> Option<&'a VersionIndex> {
+        self.iter().rev().find(|v| filter.matches(v))
+    }
+
+    /// All versions of a type, oldest first
+    #[must_use]
+    pub fn of_type(&self, version_type: VersionType) -> Vec<&'a VersionIndex> {
+        self.filter(&VersionFilter::new().version_type(version_type))
+    }
+}
+
+impl VersionManifest {
+    /// Iterate over the versions in the order they were released, oldest first.
+    /// Sorts the manifest, keep a [`VersionManifest::timeline()`] around for repeated queries
+    #[must_use]
+    pub fn iter_chronological(&self) -> impl DoubleEndedIterator<Item = &VersionIndex> {
+        self.timeline().into_iter()
+    }
+
+    /// Versions matching the filter, oldest first, see [`VersionTimeline::filter()`]
+    #[must_use]
+    pub fn filter(&self, filter: &VersionFilter) -> Vec<&VersionIndex> {
+        self.timeline().filter(filter)
+    }
+
+    /// The newest version matching the filter
+    #[must_use]
+    pub fn latest_matching(&self, filter: &VersionFilter) -> Option<&VersionIndex> {
+        self.versions
+            .iter()
+            .filter(|v| filter.matches(v))
+            .max_by(|a, b| by_release_time(a, b))
+    }
+
+    /// All versions of a type, oldest first, see [`VersionTimeline::of_type()`]
+    #[must_use]
+    pub fn of_type(&self, version_type: VersionType) -> Vec<&VersionIndex> {
+        self.timeline().of_type(version_type)
+    }
+
+    /// The newest release of every minor line (`1.20.6` for `1.20`, `26.1.2` for `26.1`),
+    /// oldest line first
+    #[must_use]
+    pub fn latest_patches(&self) -> Vec<&VersionIndex> {
+        let mut lines: BTreeMap<(u8, u8), (u8, &VersionIndex)> = BTreeMap::new();
+
+        for version in &self.versions {
+            let Some((series, major, minor)) = version
+                .id
+                .parse::<MCVersion>()
+                .ok()
+                .and_then(|v| v.release_number())
+            else {
+                continue;
+            };
+
+            let newest = lines.entry((series, major)).or_insert((minor, version));
+            if minor > newest.0 {
+                *newest = (minor, version);
+            }
+        }
+
+        lines.into_values().map(|(_, version)| version).collect()
+    }
+
+    /// Resolves a version or alias like `latest` or `1.20.x`, see [`VersionAlias`]
+    pub fn resolve(&self, alias: &str) -> Result<&VersionIndex> {
+        self.resolve_alias(&alias.parse()?)
+    }
+
+    pub fn resolve_alias(&self, alias: &VersionAlias) -> Result<&VersionIndex> {
+        let id = match alias {
+            VersionAlias::Latest => &self.latest.release,
+            VersionAlias::LatestSnapshot => &self.latest.snapshot,
+            VersionAlias::Exact(id) => id,
+            VersionAlias::Line { .. } => {
+                let filter = VersionFilter {
+                    requirement: alias.requirement().map(Into::into),
+                    ..Default::default()
+                };
+                return self
+                    .latest_matching(&filter)
+                    .ok_or_else(|| Error::UnknownAlias(alias.to_string()));
+            }
+        };
+
+        self.versions
+            .iter()
+            .find(|v| &v.id == id)
+            .ok_or_else(|| Error::UnknownAlias(alias.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vanilla::LatestVersions;
+
+    fn manifest() -> VersionManifest {
+        let version = |id: &str, version_type, release_time: &str| VersionIndex {
+            id: id.to_owned(),
+            version_type,
+            url: String::new(),
+            time: release_time.to_owned(),
+            release_time: release_time.to_owned(),
+            sha1: String::new(),
+            compliance_level: 1,
+        };
+
+        VersionManifest {
+            latest: LatestVersions {
+                release: "26.1.1".to_owned(),
+                snapshot: "26.2-snapshot-1".to_owned(),
+            },
+            versions: vec![
+                version(
+                    "26.2-snapshot-1",
+                    VersionType::Snapshot,
+                    "2026-05-05T10:00:00+00:00",
+                ),
+                version("26.1.1", VersionType::Release, "2026-04-07T10:00:00+00:00"),
+                version("26.1", VersionType::Release, "2026-03-24T10:00:00+00:00"),
+                version(
+                    "26.1-snapshot-1",
+                    VersionType::Snapshot,
+                    "2025-12-16T10:00:00+00:00",
+                ),
+                version("1.21.11", VersionType::Release, "2025-12-09T10:00:00+00:00"),
+                version("1.21.10", VersionType::Release, "2025-10-07T10:00:00+00:00"),
+            ],
+        }
+    }
+
+    fn ids(versions: &[&VersionIndex]) -> Vec<String> {
+        versions.iter().map(|v| v.id.clone()).collect()
+    }
+
+    #[test]
+    fn filters_year_based_releases() {
+        let manifest = manifest();
+        let filter =
+            VersionFilter::new().requirement(">=1.21.11".parse::<MCVersionReqSet>().unwrap());
+        assert_eq!(
+            ids(&manifest.filter(&filter)),
+            ["1.21.11", "26.1", "26.1.1"]
+        );
+
+        assert_eq!(ids(&manifest.latest_patches()), ["1.21.11", "26.1.1"]);
+
+        let timeline = manifest.timeline();
+        assert_eq!(
+            ids(&timeline.of_type(VersionType::Snapshot)),
+            ["26.1-snapshot-1", "26.2-snapshot-1"]
+        );
+        assert_eq!(
+            timeline.latest_matching(&filter).map(|v| v.id.as_str()),
+            Some("26.1.1")
+        );
+
+        let alias: VersionAlias = "26.1.x".parse().unwrap();
+        assert_eq!(alias.to_string(), "26.1.x");
+        assert_eq!(manifest.resolve_alias(&alias).unwrap().id, "26.1.1");
+        assert_eq!(manifest.resolve("1.21.x").unwrap().id, "1.21.11");
+    }
+}
@@ -0,0 +1,259 @@
+//! Java runtimes mojang ships for each [`super::VersionJavaInfo::component`],
+//! such as `java-runtime-gamma` or `jre-legacy`
+
+use std::{collections::HashMap, env, path::Path};
+
+use futures::{stream, StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+
+use crate::{
+    download::{download_bytes, ensure_file, is_file_valid, is_safe_relative_path, verify_bytes},
+    Error, Result,
+};
+
+use super::PistonFile;
+
+pub const JAVA_RUNTIME_MANIFEST_URL: &str = "https://launchermeta.mojang.com/v1/products/java-runtime/2ec0cc96c44e5a76b9c8b7c39df7210883d12871/all.json";
+
+/// Files of a runtime are downloaded this many at a time
+const CONCURRENT_DOWNLOADS: usize = 16;
+
+/// Fetches the runtime manifest
+pub async fn fetch_java_runtime_manifest(client: &reqwest::Client) -> Result<JavaRuntimeManifest> {
+    Ok(client
+        .get(JAVA_RUNTIME_MANIFEST_URL)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?)
+}
+
+/// The runtime platform key of an os and architecture, for example `linux` or `mac-os-arm64`.
+/// `os_name` is the piston os name (`linux`, `osx` or `windows`) and `arch` is like [`env::consts::ARCH`]
+#[must_use]
+pub fn runtime_platform(os_name: &str, arch: &str) -> Option<&'static str> {
+    Some(match (os_name, arch) {
+        ("linux", "x86_64") => "linux",
+        ("linux", "x86") => "linux-i386",
+        ("osx", "x86_64") => "mac-os",
+        ("osx", "aarch64") => "mac-os-arm64",
+        ("windows", "x86_64") => "windows-x64",
+        ("windows", "x86") => "windows-x86",
+        ("windows", "aarch64") => "windows-arm64",
+        _ => return None,
+    })
+}
+
+/// The runtime platform key of the current system, see [`runtime_platform()`]
+#[must_use]
+pub fn current_runtime_platform() -> Option<&'static str> {
+    let os_name = match env::consts::OS {
+        "macos" => "osx",
+        os => os,
+    };
+    runtime_platform(os_name, env::consts::ARCH)
+}
+
+/// `all.json`: platform => component => runtimes
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[serde(transparent)]
+pub struct JavaRuntimeManifest {
+    pub platforms: HashMap<String, HashMap<String, Vec<JavaRuntime>>>,
+}
+
+impl JavaRuntimeManifest {
+    /// Find the runtime of a component (like `java-runtime-gamma`) on a platform
+    #[must_use]
+    pub fn find(&self, platform: &str, component: &str) -> Option<&JavaRuntime> {
+        self.platforms.get(platform)?.get(component)?.first()
+    }
+
+    /// Find the runtime of a component for the current system
+    #[must_use]
+    pub fn find_current(&self, component: &str) -> Option<&JavaRuntime> {
+        self.find(current_runtime_platform()?, component)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[serde(default)]
+pub struct JavaRuntime {
+    pub availability: JavaRuntimeAvailability,
+    /// The [`JavaRuntimeFiles`] of this runtime
+    pub manifest: PistonFile,
+    pub version: JavaRuntimeVersion,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[serde(default)]
+pub struct JavaRuntimeAvailability {
+    pub group: u32,
+    pub progress: u32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[serde(default)]
+pub struct JavaRuntimeVersion {
+    /// Java version, for example `17.0.8`
+    pub name: String,
+    pub released: String,
+}
+
+impl JavaRuntime {
+    /// Fetch the list of files of this runtime
+    pub async fn fetch_files(&self, client: &reqwest::Client) -> Result<JavaRuntimeFiles> {
+        let data = download_bytes(
+            client,
+            &self.manifest.url,
+            &self.manifest.sha1,
+            self.manifest.size,
+        )
+        .await?;
+        Ok(serde_json::from_slice(&data)?)
+    }
+
+    /// Download the runtime into `directory`, see [`JavaRuntimeFiles::install()`]
+    pub async fn install(&self, client: &reqwest::Client, directory: &Path) -> Result<()> {
+        self.fetch_files(client)
+            .await?
+            .install(client, directory)
+            .await
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[serde(default)]
+pub struct JavaRuntimeFiles {
+    /// Path in the runtime => file
+    pub files: HashMap<String, JavaRuntimeFile>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "type")]
+pub enum JavaRuntimeFile {
+    File {
+        downloads: Box<JavaRuntimeDownloads>,
+        #[serde(default)]
+        executable: bool,
+    },
+    Directory,
+    Link {
+        target: String,
+    },
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[serde(default)]
+pub struct JavaRuntimeDownloads {
+    pub raw: PistonFile,
+    /// The same file compressed with LZMA, missing for small files
+    pub lzma: Option<PistonFile>,
+}
+
+impl JavaRuntimeFiles {
+    /// Download every file into `directory`, creating directories and symlinks.
+    /// Files are verified against their sha1 and existing valid files are kept,
+    /// the LZMA variant is preferred when there is one
+    pub async fn install(&self, client: &reqwest::Client, directory: &Path) -> Result<()> {
+        let mut files = vec![];
+        let mut links = vec![];
+        for (name, file) in &self.files {
+            if !is_safe_relative_path(name) {
+                continue;
+            }
+
+            let path = directory.join(name);
+            match file {
+                JavaRuntimeFile::Directory => fs::create_dir_all(&path).await?,
+                JavaRuntimeFile::File {
+                    downloads,
+                    executable,
+                } => files.push((path, downloads, *executable)),
+                JavaRuntimeFile::Link { target } => links.push((path, target)),
+            }
+        }
+
+        stream::iter(files)
+            .map(|(path, downloads, executable)| async move {
+                download_runtime_file(client, &path, downloads).await?;
+                if executable {
+                    set_executable(&path).await?;
+                }
+                Ok::<_, Error>(())
+            })
+            .buffer_unordered(CONCURRENT_DOWNLOADS)
+            .try_collect::<()>()
+            .await?;
+
+        for (path, target) in links {
+            create_symlink(&path, target).await?;
+        }
+
+        Ok(())
+    }
+}
+
+async fn download_runtime_file(
+    client: &reqwest::Client,
+    path: &Path,
+    downloads: &JavaRuntimeDownloads,
+) -> Result<()> {
+    let raw = &downloads.raw;
+    let Some(lzma) = &downloads.lzma else {
+        ensure_file(client, &raw.url, path, &raw.sha1, raw.size).await?;
+        return Ok(());
+    };
+
+    if is_file_valid(path, &raw.sha1, raw.size).await? {
+        return Ok(());
+    }
+
+    let compressed = download_bytes(client, &lzma.url, &lzma.sha1, lzma.size).await?;
+    let mut data = vec![];
+    lzma_rs::lzma_decompress(&mut compressed.as_slice(), &mut data)?;
+    verify_bytes(&raw.url, &data, &raw.sha1, raw.size)?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+    fs::write(path, data).await?;
+    Ok(())
+}
+
+#[cfg(unix)]
+async fn set_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut permissions = fs::metadata(path).await?.permissions();
+    permissions.set_mode(permissions.mode() | 0o755);
+    fs::set_permissions(path, permissions).await?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+#[allow(clippy::unused_async)]
+async fn set_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(unix)]
+async fn create_symlink(path: &Path, target: &str) -> Result<()> {
+    if fs::symlink_metadata(path).await.is_ok() {
+        fs::remove_file(path).await?;
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+    fs::symlink(target, path).await?;
+    Ok(())
+}
+
+/// Runtimes for windows don't have links
+#[cfg(not(unix))]
+#[allow(clippy::unused_async)]
+async fn create_symlink(_path: &Path, _target: &str) -> Result<()> {
+    Ok(())
+}
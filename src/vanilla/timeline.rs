@@ -0,0 +1,101 @@
+use std::{cmp::Ordering, collections::HashMap};
+
+use crate::MCVersion;
+
+use super::{VersionIndex, VersionManifest};
+
+/// Chronological order of every version in a [`VersionManifest`], by `release_time`.
+/// Unlike [`MCVersion`]'s ordering this is exact for snapshots and special versions
+#[derive(Debug, Clone, Default)]
+pub struct VersionTimeline<'a> {
+    /// Oldest first
+    versions: Vec<&'a VersionIndex>,
+    positions: HashMap<&'a str, usize>,
+}
+
+/// Orders versions by `release_time`
+pub(crate) fn by_release_time(a: &VersionIndex, b: &VersionIndex) -> Ordering {
+    // release times are RFC 3339 timestamps in UTC, so they sort as strings
+    a.release_time.cmp(&b.release_time)
+}
+
+impl<'a> VersionTimeline<'a> {
+    #[must_use]
+    pub fn new(manifest: &'a VersionManifest) -> Self {
+        let mut versions: Vec<&VersionIndex> = manifest.versions.iter().collect();
+        versions.sort_by(|a, b| by_release_time(a, b));
+
+        let positions = versions
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (v.id.as_str(), i))
+            .collect();
+
+        Self {
+            versions,
+            positions,
+        }
+    }
+
+    /// Index of the version in chronological order
+    #[must_use]
+    pub fn position(&self, id: &str) -> Option<usize> {
+        self.positions.get(id).copied()
+    }
+
+    #[must_use]
+    pub fn contains(&self, id: &str) -> bool {
+        self.positions.contains_key(id)
+    }
+
+    /// Compares two version ids chronologically.
+    /// Falls back to [`MCVersion`]'s ordering if either isn't in the timeline
+    #[must_use]
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        match (self.position(a), self.position(b)) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            _ => match (a.parse::<MCVersion>(), b.parse::<MCVersion>()) {
+                (Ok(a), Ok(b)) => a.cmp(&b),
+                _ => a.cmp(b),
+            },
+        }
+    }
+
+    /// Whether `a` came out after `b`, for example `is_after("23w45a", "1.20.2")`
+    #[must_use]
+    pub fn is_after(&self, a: &str, b: &str) -> bool {
+        self.compare(a, b) == Ordering::Greater
+    }
+
+    /// Whether `a` came out before `b`
+    #[must_use]
+    pub fn is_before(&self, a: &str, b: &str) -> bool {
+        self.compare(a, b) == Ordering::Less
+    }
+
+    /// Iterate over the versions, oldest first
+    #[must_use]
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &'a VersionIndex> + '_ {
+        self.versions.iter().copied()
+    }
+
+    /// Sorts version ids chronologically, see [`Self::compare()`]
+    pub fn sort<S: AsRef<str>>(&self, ids: &mut [S]) {
+        ids.sort_by(|a, b| self.compare(a.as_ref(), b.as_ref()));
+    }
+}
+
+impl<'a> IntoIterator for VersionTimeline<'a> {
+    type Item = &'a VersionIndex;
+    type IntoIter = std::vec::IntoIter<&'a VersionIndex>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.versions.into_iter()
+    }
+}
+
+impl<'a> From<&'a VersionManifest> for VersionTimeline<'a> {
+    fn from(manifest: &'a VersionManifest) -> Self {
+        Self::new(manifest)
+    }
+}
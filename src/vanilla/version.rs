@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
-use serde::{Deserialize, Serialize};
+use regex::Regex;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
 use super::VersionType;
 
@@ -9,6 +10,11 @@ use super::VersionType;
 #[serde(rename_all = "camelCase")]
 pub struct VersionInfo {
     pub id: String,
+    /// Id of the version this profile extends, set by mod loaders like fabric and forge.
+    /// See [`VersionInfo::inherit()`]
+    pub inherits_from: Option<String>,
+    /// Id of the version whose client jar is used, defaults to `id`
+    pub jar: Option<String>,
     pub assets: String,
     pub asset_index: PistonFile,
     pub java_version: VersionJavaInfo,
@@ -50,6 +56,7 @@ pub struct VersionJavaInfo {
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[serde(default)]
 pub struct VersionArguments {
     pub game: Vec<PistonArgument>,
     pub jvm: Vec<PistonArgument>,
@@ -80,29 +87,37 @@ pub enum ArgumentValue {
 pub struct PistonLibrary {
     pub name: String,
     pub downloads: PistonLibraryDownload,
+    /// Maven repository of libraries without `downloads`, used by mod loader profiles
+    pub url: Option<String>,
     pub rules: Vec<PistonRule>,
 
     /// Present on old versions, something like this:
+    /// ```json
     /// "extract": {
     ///     "exclude": ["META-INF/"],
     ///     "name": "tv.twitch:twitch-external-platform:4.5"
     /// }
+    /// ```
     pub extract: Option<PistonExtractLibrary>,
 
     /// Present on old versions, some weird stuff involving classifiers
+    /// ```json
     /// "natives": {
     ///     "linux":   "natives-linux"
     ///     "osx":     "natives-osx"
     ///     "windows": "natives-windows-${arch}"
     /// }
+    /// ```
     pub natives: Option<HashMap<String, String>>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[serde(default)]
 #[serde(rename_all = "camelCase")]
 pub struct PistonExtractLibrary {
-    exclude: Vec<String>,
-    name: Option<String>,
+    /// Path prefixes in the jar that aren't extracted
+    pub exclude: Vec<String>,
+    pub name: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -117,19 +132,47 @@ pub enum PistonRule {
 #[serde(rename_all = "camelCase")]
 #[serde(default)]
 pub struct PistonRuleConstraints {
-    pub os: Option<PistonOs>,
+    pub os: Option<PistonOsConstraint>,
+    /// Feature name => required state, see [`super::LaunchFeatures`]
     pub features: Option<HashMap<String, bool>>,
 }
 
-/*
-#[derive(Debug, Clone, Deserialize, Serialize)]
+/// The os a rule applies to, empty fields match any os
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
 #[serde(rename_all = "camelCase")]
-pub struct PistonRuleConstraintFeature {
-    pub is_demo_user: bool,
-    pub has_custom_resolution: bool,
-    pub has_quick_plays_support: bool,
+#[serde(default)]
+pub struct PistonOsConstraint {
+    pub name: String,
+    pub arch: String,
+    pub version: Option<OsVersionRegex>,
+}
 
-} */
+/// A regex on the os version in a rule, like `^10\.`.
+/// Compiled once when deserialized
+#[derive(Debug, Clone)]
+pub struct OsVersionRegex(pub Regex);
+
+impl PartialEq for OsVersionRegex {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+impl Eq for OsVersionRegex {}
+
+impl Serialize for OsVersionRegex {
+    fn serialize<S: Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for OsVersionRegex {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> core::result::Result<Self, D::Error> {
+        Regex::new(&String::deserialize(deserializer)?)
+            .map(Self)
+            .map_err(de::Error::custom)
+    }
+}
 
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
 #[serde(rename_all = "camelCase")]
@@ -146,19 +189,23 @@ pub struct PistonLibraryDownload {
     pub artifact: PistonFile,
 
     /// Conditional files that may be needed to be downloaded alongside the library
-    /// The HashMap key specifies a classifier as additional information for downloading files
+    /// The `HashMap` key specifies a classifier as additional information for downloading files
     pub classifiers: Option<HashMap<String, PistonFile>>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[serde(default)]
 #[serde(rename_all = "camelCase")]
 pub struct LoggingInfoWrapper {
-    pub client: VersionLoggingInfo,
+    pub client: Option<VersionLoggingInfo>,
+    pub server: Option<VersionLoggingInfo>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[serde(default)]
 #[serde(rename_all = "camelCase")]
 pub struct VersionLoggingInfo {
+    /// Jvm argument using the config, like `-Dlog4j.configurationFile=${path}`
     pub argument: String,
     pub file: PistonFile,
     #[serde(rename = "type")]
@@ -174,9 +221,9 @@ pub struct PistonFile {
     pub size: u64,
     pub url: String,
 
-    /// (AssetIndex only) The game version ID the assets are for
+    /// (`AssetIndex` only) The game version ID the assets are for
     pub id: Option<String>,
-    /// (AssetIndex only) The size of the game version's assets
+    /// (`AssetIndex` only) The size of the game version's assets
     pub total_size: Option<u64>,
 
     /// Only present on library files
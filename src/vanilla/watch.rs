@@ -0,0 +1,151 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+
+use crate::Result;
+
+use super::{fetch_version_manifest, VersionIndex, VersionManifest};
+
+/// A difference between two version manifests
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub enum ManifestEvent {
+    VersionAdded(VersionIndex),
+    LatestReleaseChanged {
+        old: String,
+        new: String,
+    },
+    LatestSnapshotChanged {
+        old: String,
+        new: String,
+    },
+    /// Mojang re-published the version's json
+    VersionUpdated {
+        old_sha1: String,
+        version: VersionIndex,
+    },
+}
+
+impl VersionManifest {
+    /// What changed from this manifest to `newer`.
+    /// Added and updated versions come first, oldest first, followed by changes of the latest versions
+    #[must_use]
+    pub fn diff(&self, newer: &VersionManifest) -> Vec<ManifestEvent> {
+        let old: HashMap<&str, &VersionIndex> =
+            self.versions.iter().map(|v| (v.id.as_str(), v)).collect();
+
+        let mut events: Vec<ManifestEvent> = newer
+            .iter_chronological()
+            .filter_map(|version| match old.get(version.id.as_str()) {
+                None => Some(ManifestEvent::VersionAdded(version.clone())),
+                Some(old) if old.sha1 != version.sha1 => Some(ManifestEvent::VersionUpdated {
+                    old_sha1: old.sha1.clone(),
+                    version: version.clone(),
+                }),
+                Some(_) => None,
+            })
+            .collect();
+
+        if self.latest.release != newer.latest.release {
+            events.push(ManifestEvent::LatestReleaseChanged {
+                old: self.latest.release.clone(),
+                new: newer.latest.release.clone(),
+            });
+        }
+
+        if self.latest.snapshot != newer.latest.snapshot {
+            events.push(ManifestEvent::LatestSnapshotChanged {
+                old: self.latest.snapshot.clone(),
+                new: newer.latest.snapshot.clone(),
+            });
+        }
+
+        events
+    }
+}
+
+/// Polls the version manifest for changes, keeping the last seen manifest in a file
+/// so changes made while not running are noticed too
+pub struct ManifestWatcher {
+    pub client: reqwest::Client,
+    pub state_path: PathBuf,
+    /// Time between polls in [`Self::next_events()`]
+    pub interval: Duration,
+    last: Option<VersionManifest>,
+}
+
+impl ManifestWatcher {
+    /// Creates a watcher, loading the last seen manifest from `state_path` if it exists
+    pub async fn new(
+        client: reqwest::Client,
+        state_path: &Path,
+        interval: Duration,
+    ) -> Result<Self> {
+        let last = match fs::read(state_path).await {
+            Ok(data) => Some(serde_json::from_slice(&data)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(Self {
+            client,
+            state_path: state_path.to_owned(),
+            interval,
+            last,
+        })
+    }
+
+    /// The last seen manifest
+    #[must_use]
+    pub fn last(&self) -> Option<&VersionManifest> {
+        self.last.as_ref()
+    }
+
+    /// Fetches the manifest once and returns what changed since the last poll.
+    /// Without a last seen manifest this only records the current one and returns no events
+    pub async fn poll(&mut self) -> Result<Vec<ManifestEvent>> {
+        let manifest = fetch_version_manifest(&self.client).await?;
+        let events = self
+            .last
+            .as_ref()
+            .map(|last| last.diff(&manifest))
+            .unwrap_or_default();
+
+        if self.last.as_ref() != Some(&manifest) {
+            self.save(&manifest).await?;
+            self.last = Some(manifest);
+        }
+
+        Ok(events)
+    }
+
+    /// Polls every `interval` until something changed
+    pub async fn next_events(&mut self) -> Result<Vec<ManifestEvent>> {
+        loop {
+            let events = self.poll().await?;
+            if !events.is_empty() {
+                return Ok(events);
+            }
+
+            tokio::time::sleep(self.interval).await;
+        }
+    }
+
+    async fn save(&self, manifest: &VersionManifest) -> Result<()> {
+        if let Some(parent) = self.state_path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        // write next to the state and move it over, so a crash can't leave half a file
+        let mut partial_name = self.state_path.file_name().unwrap_or_default().to_owned();
+        partial_name.push(".part");
+        let partial = self.state_path.with_file_name(partial_name);
+        fs::write(&partial, serde_json::to_vec(manifest)?).await?;
+        fs::rename(&partial, &self.state_path).await?;
+        Ok(())
+    }
+}
@@ -1,21 +1,71 @@
-use std::str::FromStr;
+use std::{cmp::Ordering, fmt, str::FromStr, sync::LazyLock};
 
-use crate::{Error, Result};
+use regex::Regex;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
-#[derive(Debug)]
-pub struct MCVersion {
-    pub major: u8,
-    pub minor: u8,
+use crate::{vanilla::VersionType, Error, Result};
 
-    pub is_release: bool,
-    pub extra: String,
+/// A minecraft version id, as found in piston-meta's version manifest.
+/// `major` and `minor` refer to `1.{major}.{minor}`, ids of the year-based scheme
+/// used since 26.1 are [`MCVersion::Drop`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MCVersion {
+    /// `1.20.4`, `1.20`
+    Release { major: u8, minor: u8 },
+    /// `1.21-pre1`, `1.14 Pre-Release 2`
+    PreRelease { major: u8, minor: u8, pre: u8 },
+    /// `1.21-rc2`
+    ReleaseCandidate { major: u8, minor: u8, rc: u8 },
+    /// `24w14a`
+    Snapshot { year: u8, week: u8, letter: char },
+    /// `b1.7.3`, holds the full id
+    OldBeta(String),
+    /// `a1.0.4`, `c0.30_01c`, `inf-20100618`, `rd-132211`, holds the full id
+    OldAlpha(String),
+    /// `26.1`, `26.1.1`, `26.1-snapshot-1`, `26.1-pre-1`, `26.1-rc-1` -
+    /// `{year}.{drop}.{hotfix}` and the versions leading up to it
+    Drop {
+        year: u8,
+        drop: u8,
+        hotfix: u8,
+        stage: DropStage,
+    },
+    /// April Fools and other one-off versions such as `1.RV-Pre1` or `24w14potato`,
+    /// also anything else that couldn't be classified
+    Special(String),
 }
 
-#[derive(Debug)]
+/// How far along a [`MCVersion::Drop`] is, ordered from first snapshot to release
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum DropStage {
+    /// `-snapshot-N`
+    Snapshot(u8),
+    /// `-pre-N`
+    PreRelease(u8),
+    /// `-rc-N`
+    ReleaseCandidate(u8),
+    Release,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct MCVersionReq {
     pub comparators: Vec<Comparator>,
 }
 
+/// Key of `1.0`, nothing is below it. See [`Comparator::range()`]
+const KEY_START: u32 = 1 << 16;
+/// Exclusive upper bound of release keys
+const KEY_END: u32 = 1 << 24;
+
+fn release_key(series: u8, major: u8, minor: u8) -> u32 {
+    u32::from(series) << 16 | u32::from(major) << 8 | u32::from(minor)
+}
+
+fn key_release(key: u32) -> (u8, u8, u8) {
+    let [_, series, major, minor] = key.to_be_bytes();
+    (series, major, minor)
+}
+
 impl MCVersionReq {
     pub const ANY: Self = MCVersionReq {
         comparators: Vec::new(),
@@ -25,31 +75,257 @@ impl MCVersionReq {
     pub fn matches(&self, version: &MCVersion) -> bool {
         self.comparators.iter().all(|cmp| cmp.matches(version))
     }
+
+    /// The half-open range of release keys this requirement matches, see [`Comparator::range()`]
+    fn range(&self) -> (u32, u32) {
+        self.comparators
+            .iter()
+            .copied()
+            .map(Comparator::range)
+            .fold((KEY_START, KEY_END), |(lo, hi), (cmp_lo, cmp_hi)| {
+                (lo.max(cmp_lo), hi.min(cmp_hi))
+            })
+    }
+
+    /// Builds the canonical requirement for a range of release keys
+    fn from_range(lo: u32, hi: u32) -> Self {
+        let comparators = if lo >= hi {
+            // nothing is below 1.0
+            vec![Comparator::new(Op::Less, 0, 0)]
+        } else if lo <= KEY_START && hi == KEY_END {
+            vec![]
+        } else if hi == lo + 1 {
+            vec![Comparator::from_key(Op::Exact, lo)]
+        } else if lo.is_multiple_of(256) && hi == lo + 256 {
+            vec![Comparator::from_key(Op::Wildcard, lo)]
+        } else {
+            let mut list = vec![];
+            if lo > KEY_START {
+                list.push(Comparator::from_key(Op::GreaterEq, lo));
+            }
+            if hi.is_multiple_of(256) && hi != KEY_END {
+                list.push(Comparator::from_key(Op::Less, hi));
+            } else if hi != KEY_END {
+                list.push(Comparator::from_key(Op::LessEq, hi - 1));
+            }
+            list
+        };
+
+        Self { comparators }
+    }
+
+    /// Whether no release can satisfy this requirement, such as `>=1.21, <1.20`
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        let (lo, hi) = self.range();
+        lo >= hi
+    }
+
+    /// Requirement matching releases that satisfy both `self` and `other`,
+    /// in canonical form (`>=1.19, <1.21` intersected with `1.20.x` is `1.20.x`)
+    #[must_use]
+    pub fn intersect(&self, other: &MCVersionReq) -> MCVersionReq {
+        let (lo, hi) = self.range();
+        let (other_lo, other_hi) = other.range();
+        Self::from_range(lo.max(other_lo), hi.min(other_hi))
+    }
+
+    /// Requirement set matching releases that satisfy either `self` or `other`
+    #[must_use]
+    pub fn union(&self, other: &MCVersionReq) -> MCVersionReqSet {
+        MCVersionReqSet::from(self.clone()).union(&other.clone().into())
+    }
 }
 
 impl Comparator {
+    /// Only releases can match a comparator
     #[must_use]
     pub fn matches(&self, version: &MCVersion) -> bool {
+        let Some(version) = version.release_number() else {
+            return false;
+        };
+        let target = (self.series, self.major, self.minor);
+
         match self.op {
-            Op::Exact => self.major == version.major && self.minor == version.minor,
-            Op::Greater => {
-                self.major < version.major
-                    || (self.major == version.major && self.minor < version.minor)
-            }
-            Op::GreaterEq => {
-                self.major <= version.major
-                    || (self.major == version.major && self.minor <= version.minor)
+            Op::Exact => version == target,
+            Op::Greater => version > target,
+            Op::GreaterEq => version >= target,
+            Op::Less => version < target,
+            Op::LessEq => version <= target,
+            Op::Wildcard => (version.0, version.1) == (self.series, self.major),
+        }
+    }
+
+    /// The half-open range of release keys (`series << 16 | major << 8 | minor`)
+    /// this comparator matches
+    fn range(self) -> (u32, u32) {
+        let key = release_key(self.series, self.major, self.minor);
+        match self.op {
+            Op::Exact => (key, key + 1),
+            Op::Greater => (key + 1, KEY_END),
+            Op::GreaterEq => (key, KEY_END),
+            Op::Less => (KEY_START, key),
+            Op::LessEq => (KEY_START, key + 1),
+            Op::Wildcard => {
+                let start = release_key(self.series, self.major, 0);
+                (start, start + 256)
             }
-            Op::Less => {
-                self.major > version.major
-                    || (self.major == version.major && self.minor > version.minor)
+        }
+    }
+}
+
+/// Any of several requirements, such as `1.8.8 || >=1.16.5, <1.21`.
+/// Unlike [`MCVersionReq`] this can represent the union of requirements
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MCVersionReqSet {
+    /// Non-empty, non-overlapping requirements ordered from oldest to newest.
+    /// A set without alternatives matches nothing
+    pub alternatives: Vec<MCVersionReq>,
+}
+
+impl MCVersionReqSet {
+    /// A set matching every version
+    #[must_use]
+    pub fn any() -> Self {
+        Self {
+            alternatives: vec![MCVersionReq::ANY],
+        }
+    }
+
+    #[must_use]
+    pub fn matches(&self, version: &MCVersion) -> bool {
+        self.alternatives.iter().any(|req| req.matches(version))
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.alternatives.iter().all(MCVersionReq::is_empty)
+    }
+
+    /// Merges overlapping and adjacent ranges, drops empty ones
+    fn from_ranges(mut ranges: Vec<(u32, u32)>) -> Self {
+        ranges.retain(|(lo, hi)| lo < hi);
+        ranges.sort_unstable();
+
+        let mut merged: Vec<(u32, u32)> = vec![];
+        for (lo, hi) in ranges {
+            match merged.last_mut() {
+                Some(last) if lo <= last.1 => last.1 = last.1.max(hi),
+                _ => merged.push((lo, hi)),
             }
-            Op::LessEq => {
-                self.major >= version.major
-                    || (self.major == version.major && self.minor >= version.minor)
+        }
+
+        Self {
+            alternatives: merged
+                .into_iter()
+                .map(|(lo, hi)| MCVersionReq::from_range(lo, hi))
+                .collect(),
+        }
+    }
+
+    fn ranges(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.alternatives.iter().map(MCVersionReq::range)
+    }
+
+    /// Set matching releases that satisfy either set
+    #[must_use]
+    pub fn union(&self, other: &MCVersionReqSet) -> MCVersionReqSet {
+        Self::from_ranges(self.ranges().chain(other.ranges()).collect())
+    }
+
+    /// Set matching releases that satisfy both sets
+    #[must_use]
+    pub fn intersect(&self, other: &MCVersionReqSet) -> MCVersionReqSet {
+        Self::from_ranges(
+            self.ranges()
+                .flat_map(|(lo, hi)| {
+                    other
+                        .ranges()
+                        .map(move |(other_lo, other_hi)| (lo.max(other_lo), hi.min(other_hi)))
+                })
+                .collect(),
+        )
+    }
+}
+
+impl MCVersionReqSet {
+    /// Builds a set from a list of supported versions as published by Hangar or Modrinth.
+    /// Entries can be exact versions (`1.20` is only 1.20), inclusive ranges (`1.19-1.20.4`)
+    /// or wildcards (`1.20.x`). Entries that aren't releases, such as snapshots, are skipped
+    pub fn from_platform_versions<I, S>(versions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self::from_ranges(
+            versions
+                .into_iter()
+                .filter_map(|entry| platform_version_range(entry.as_ref().trim()))
+                .collect(),
+        )
+    }
+
+    /// Builds a set from a comma separated list of platform versions such as
+    /// `1.8.8, 1.12.2, 1.16-1.20.4`, see [`Self::from_platform_versions()`]
+    #[must_use]
+    pub fn from_platform_versions_formatted(text: &str) -> Self {
+        Self::from_platform_versions(text.split(','))
+    }
+}
+
+fn platform_version_range(entry: &str) -> Option<(u32, u32)> {
+    let release = |s: &str| {
+        let (series, major, minor) = s.parse::<MCVersion>().ok()?.release_number()?;
+        Some(release_key(series, major, minor))
+    };
+
+    if let Some((lower, upper)) = entry.split_once('-') {
+        if let (Some(lower), Some(upper)) = (release(lower), release(upper)) {
+            return Some((lower, upper + 1));
+        }
+    }
+
+    if matches!(entry.rsplit('.').next(), Some("x" | "X" | "*")) {
+        return entry.parse::<MCVersionReq>().ok().map(|req| req.range());
+    }
+
+    release(entry).map(|key| (key, key + 1))
+}
+
+impl From<MCVersionReq> for MCVersionReqSet {
+    fn from(req: MCVersionReq) -> Self {
+        Self::from_ranges(vec![req.range()])
+    }
+}
+
+/// Parses `||`-separated [`MCVersionReq`]s
+impl FromStr for MCVersionReqSet {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self> {
+        let ranges = text
+            .split("||")
+            .map(|req| req.parse::<MCVersionReq>().map(|req| req.range()))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self::from_ranges(ranges))
+    }
+}
+
+impl fmt::Display for MCVersionReqSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.alternatives.is_empty() {
+            return write!(f, "{}", MCVersionReq::from_range(0, 0));
+        }
+
+        for (i, req) in self.alternatives.iter().enumerate() {
+            if i != 0 {
+                f.write_str(" || ")?;
             }
-            Op::Wildcard => true,
+            write!(f, "{req}")?;
         }
+
+        Ok(())
     }
 }
 
@@ -59,65 +335,864 @@ impl Default for MCVersionReq {
     }
 }
 
-#[derive(Debug)]
+/// Compares releases by `{series}.{major}.{minor}`: `1.20.4` is series 1, major 20, minor 4
+/// and year-based `26.1.2` is series 26, major 1, minor 2
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Comparator {
     pub op: Op,
+    /// `1`, or the year of year-based releases
+    pub series: u8,
     pub major: u8,
     pub minor: u8,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Op {
     Exact,
     Greater,
     GreaterEq,
     Less,
     LessEq,
+    /// Matches every version of `{series}.{major}`, `minor` is ignored
     Wildcard,
 }
 
+static RELEASE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^1\.(\d+)(?:\.(\d+))?$").unwrap());
+static PRE_RELEASE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^1\.(\d+)(?:\.(\d+))?(?:-pre| Pre-Release )(\d+)$").unwrap());
+static RELEASE_CANDIDATE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^1\.(\d+)(?:\.(\d+))?-rc(\d+)$").unwrap());
+static DROP_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(\d{2})\.(\d+)(?:\.(\d+))?(?:-(snapshot|pre|rc)-(\d+))?$").unwrap()
+});
+static SNAPSHOT_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\d{2})w(\d{2})([a-z])$").unwrap());
+static WEEK_PREFIX_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\d{2})w(\d{2})").unwrap());
+static NUMBER_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\d+").unwrap());
+
+/// `(major, minor, year, week)` - the week (`YYwWW`, like snapshot ids) releases came out.
+/// Year-based releases are listed as `(year, drop, ..)`. Releases not listed share the week of the closest listed release before them.
+/// Only used to place snapshots between releases when no manifest is available,
+/// see [`crate::vanilla::VersionTimeline`] for the accurate ordering
+const RELEASE_WEEKS: &[(u8, u8, u8, u8)] = &[
+    (0, 0, 11, 46),
+    (1, 0, 12, 2),
+    (2, 0, 12, 9),
+    (3, 0, 12, 31),
+    (4, 0, 12, 43),
+    (4, 6, 12, 51),
+    (5, 0, 13, 11),
+    (6, 0, 13, 27),
+    (6, 4, 13, 38),
+    (7, 0, 13, 43),
+    (7, 4, 13, 50),
+    (7, 6, 14, 15),
+    (8, 0, 14, 36),
+    (9, 0, 16, 9),
+    (9, 3, 16, 19),
+    (10, 0, 16, 23),
+    (11, 0, 16, 46),
+    (11, 1, 16, 51),
+    (12, 0, 17, 23),
+    (12, 1, 17, 31),
+    (12, 2, 17, 38),
+    (13, 0, 18, 29),
+    (13, 1, 18, 34),
+    (13, 2, 18, 43),
+    (14, 0, 19, 17),
+    (14, 4, 19, 29),
+    (15, 0, 19, 50),
+    (15, 2, 20, 3),
+    (16, 0, 20, 26),
+    (16, 2, 20, 33),
+    (16, 5, 21, 2),
+    (17, 0, 21, 23),
+    (17, 1, 21, 27),
+    (18, 0, 21, 48),
+    (18, 2, 22, 9),
+    (19, 0, 22, 23),
+    (19, 1, 22, 30),
+    (19, 3, 22, 49),
+    (19, 4, 23, 11),
+    (20, 0, 23, 23),
+    (20, 2, 23, 38),
+    (20, 3, 23, 49),
+    (20, 5, 24, 17),
+    (21, 0, 24, 24),
+    (21, 2, 24, 43),
+    (21, 4, 24, 49),
+    (21, 5, 25, 13),
+    (21, 6, 25, 25),
+    (21, 7, 25, 27),
+    (21, 9, 25, 40),
+    (21, 11, 25, 50),
+    (26, 1, 26, 12),
+];
+
+/// `(id, year, week)` of special versions whose id doesn't start with `YYwWW`
+const SPECIAL_WEEKS: &[(&str, u8, u8)] = &[
+    ("2point0_red", 13, 14),
+    ("2point0_blue", 13, 14),
+    ("2point0_purple", 13, 14),
+    ("1.RV-Pre1", 16, 13),
+    ("3D Shareware v1.34", 19, 14),
+];
+
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct SortKey<'a> {
+    /// old alpha, old beta, everything else
+    era: u8,
+    /// `(year, week)`, only used for modern versions
+    week: (u8, u8),
+    /// release-ish, snapshot, special - or the kind of old alpha (rd, c, inf, a)
+    group: u8,
+    numbers: Vec<u32>,
+    text: &'a str,
+}
+
+fn release_week(major: u8, minor: u8) -> (u8, u8) {
+    RELEASE_WEEKS
+        .iter()
+        .rev()
+        .find(|(ma, mi, ..)| (*ma, *mi) <= (major, minor))
+        .map_or((0, 0), |(.., year, week)| (*year, *week))
+}
+
+fn legacy_numbers(text: &str) -> Vec<u32> {
+    NUMBER_REGEX
+        .find_iter(text)
+        .map(|m| m.as_str().parse().unwrap_or(u32::MAX))
+        .collect()
+}
+
 impl MCVersion {
     #[must_use]
     pub fn new(major: u8, minor: u8) -> Self {
-        Self {
-            major,
-            minor,
-            is_release: true,
-            extra: String::new(),
+        Self::Release { major, minor }
+    }
+
+    /// `(major, minor)` if this is a `1.x` release
+    #[must_use]
+    pub fn release(&self) -> Option<(u8, u8)> {
+        match self {
+            Self::Release { major, minor } => Some((*major, *minor)),
+            _ => None,
         }
     }
+
+    /// `(series, major, minor)` if this is a release of either scheme,
+    /// `(1, 20, 4)` for `1.20.4` and `(26, 1, 0)` for `26.1`. See [`Comparator`]
+    #[must_use]
+    pub fn release_number(&self) -> Option<(u8, u8, u8)> {
+        match self {
+            Self::Release { major, minor } => Some((1, *major, *minor)),
+            Self::Drop {
+                year,
+                drop,
+                hotfix,
+                stage: DropStage::Release,
+            } => Some((*year, *drop, *hotfix)),
+            _ => None,
+        }
+    }
+
+    /// `(major, minor)` of the release this version is or leads up to.
+    /// Only known for `1.x` releases, pre-releases and release candidates
+    #[must_use]
+    pub fn target_release(&self) -> Option<(u8, u8)> {
+        match self {
+            Self::Release { major, minor }
+            | Self::PreRelease { major, minor, .. }
+            | Self::ReleaseCandidate { major, minor, .. } => Some((*major, *minor)),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn is_release(&self) -> bool {
+        matches!(
+            self,
+            Self::Release { .. }
+                | Self::Drop {
+                    stage: DropStage::Release,
+                    ..
+                }
+        )
+    }
+
+    #[must_use]
+    pub fn is_snapshot(&self) -> bool {
+        matches!(
+            self,
+            Self::Snapshot { .. }
+                | Self::Drop {
+                    stage: DropStage::Snapshot(_),
+                    ..
+                }
+        )
+    }
+
+    /// Pre-releases and release candidates
+    #[must_use]
+    pub fn is_pre_release(&self) -> bool {
+        matches!(
+            self,
+            Self::PreRelease { .. }
+                | Self::ReleaseCandidate { .. }
+                | Self::Drop {
+                    stage: DropStage::PreRelease(_) | DropStage::ReleaseCandidate(_),
+                    ..
+                }
+        )
+    }
+
+    /// The type piston-meta lists this version under.
+    /// Pre-releases, release candidates and special versions are listed as snapshots
+    #[must_use]
+    pub fn version_type(&self) -> VersionType {
+        match self {
+            Self::Release { .. }
+            | Self::Drop {
+                stage: DropStage::Release,
+                ..
+            } => VersionType::Release,
+            Self::OldBeta(_) => VersionType::OldBeta,
+            Self::OldAlpha(_) => VersionType::OldAlpha,
+            _ => VersionType::Snapshot,
+        }
+    }
+}
+
+impl MCVersion {
+    fn sort_key(&self) -> SortKey<'_> {
+        let key = |era, week, group, numbers, text| SortKey {
+            era,
+            week,
+            group,
+            numbers,
+            text,
+        };
+
+        match self {
+            Self::OldAlpha(id) => {
+                let group = ["rd-", "c", "inf-", "a"]
+                    .iter()
+                    .zip(0..)
+                    .find(|(prefix, _)| id.starts_with(*prefix))
+                    .map_or(0, |(_, group)| group);
+                key(0, (0, 0), group, legacy_numbers(id), id)
+            }
+            Self::OldBeta(id) => key(1, (0, 0), 0, legacy_numbers(id), id),
+            Self::Release { major, minor } => key(
+                2,
+                release_week(*major, *minor),
+                0,
+                vec![(*major).into(), (*minor).into(), 2, 0],
+                "",
+            ),
+            Self::PreRelease { major, minor, pre } => key(
+                2,
+                release_week(*major, *minor),
+                0,
+                vec![(*major).into(), (*minor).into(), 0, (*pre).into()],
+                "",
+            ),
+            Self::ReleaseCandidate { major, minor, rc } => key(
+                2,
+                release_week(*major, *minor),
+                0,
+                vec![(*major).into(), (*minor).into(), 1, (*rc).into()],
+                "",
+            ),
+            Self::Snapshot { year, week, letter } => {
+                key(2, (*year, *week), 1, vec![(*letter).into()], "")
+            }
+            Self::Drop {
+                year,
+                drop,
+                hotfix,
+                stage,
+            } => {
+                let (stage, number) = match stage {
+                    DropStage::Snapshot(n) => (0, *n),
+                    DropStage::PreRelease(n) => (1, *n),
+                    DropStage::ReleaseCandidate(n) => (2, *n),
+                    DropStage::Release => (3, 0),
+                };
+                key(
+                    2,
+                    release_week(*year, *drop),
+                    0,
+                    vec![
+                        (*year).into(),
+                        (*drop).into(),
+                        (*hotfix).into(),
+                        stage,
+                        number.into(),
+                    ],
+                    "",
+                )
+            }
+            Self::Special(id) => {
+                let week = WEEK_PREFIX_REGEX
+                    .captures(id)
+                    .and_then(|caps| Some((caps[1].parse().ok()?, caps[2].parse().ok()?)))
+                    .or_else(|| {
+                        SPECIAL_WEEKS
+                            .iter()
+                            .find(|(special, ..)| special == id)
+                            .map(|(_, year, week)| (*year, *week))
+                    })
+                    // unknown, assume it's newer than everything
+                    .unwrap_or((u8::MAX, u8::MAX));
+                key(2, week, 2, vec![], id)
+            }
+        }
+    }
+}
+
+/// Approximate chronological order that works without a manifest:
+/// old alphas, old betas, then everything else by the week it came out in.
+/// Releases, pre-releases and release candidates are ordered by their numbers,
+/// snapshots are placed between releases using a built-in table of release dates.
+///
+/// Use [`crate::vanilla::VersionTimeline`] when a manifest is available
+impl Ord for MCVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.sort_key().cmp(&other.sort_key())
+    }
+}
+
+impl PartialOrd for MCVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
+/// Parses any version id, surrounding whitespace is ignored.
+/// Ids that don't follow a known scheme become [`MCVersion::Special`],
+/// only an empty string is an error
 impl FromStr for MCVersion {
     type Err = Error;
 
     fn from_str(text: &str) -> Result<Self> {
-        if !text.contains('.') || text.contains('-') || text.contains(' ') {
-            Ok(Self {
-                major: 0,
-                minor: 0,
-                is_release: false,
-                extra: text.to_owned(),
-            })
-        } else {
-            let vec: Vec<&str> = text.split('.').collect();
-            if vec.len() < 2 || vec[0] != "1" {
-                return Err(Error::InvalidVersion(text.to_owned()));
-            }
-            Ok(Self {
-                major: vec[1].parse().unwrap_or(0),
-                minor: vec[2].parse().unwrap_or(0),
-                is_release: true,
-                extra: String::new(),
-            })
+        let text = text.trim();
+        if text.is_empty() {
+            return Err(Error::InvalidVersion(text.to_owned()));
         }
+
+        Ok(parse_version(text).unwrap_or_else(|| Self::Special(text.to_owned())))
+    }
+}
+
+/// Writes the version id as piston-meta spells it
+impl fmt::Display for MCVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let release = |f: &mut fmt::Formatter<'_>, major: u8, minor: u8| {
+            if minor == 0 {
+                write!(f, "1.{major}")
+            } else {
+                write!(f, "1.{major}.{minor}")
+            }
+        };
+
+        match self {
+            Self::Release { major, minor } => release(f, *major, *minor),
+            Self::PreRelease { major, minor, pre } => {
+                release(f, *major, *minor)?;
+                // 1.14 through 1.14.2 used a different format
+                if *major == 14 && *minor <= 2 {
+                    write!(f, " Pre-Release {pre}")
+                } else {
+                    write!(f, "-pre{pre}")
+                }
+            }
+            Self::ReleaseCandidate { major, minor, rc } => {
+                release(f, *major, *minor)?;
+                write!(f, "-rc{rc}")
+            }
+            Self::Snapshot { year, week, letter } => write!(f, "{year:02}w{week:02}{letter}"),
+            Self::Drop {
+                year,
+                drop,
+                hotfix,
+                stage,
+            } => {
+                write!(f, "{year}.{drop}")?;
+                if *hotfix != 0 {
+                    write!(f, ".{hotfix}")?;
+                }
+                match stage {
+                    DropStage::Snapshot(n) => write!(f, "-snapshot-{n}"),
+                    DropStage::PreRelease(n) => write!(f, "-pre-{n}"),
+                    DropStage::ReleaseCandidate(n) => write!(f, "-rc-{n}"),
+                    DropStage::Release => Ok(()),
+                }
+            }
+            Self::OldBeta(id) | Self::OldAlpha(id) | Self::Special(id) => f.write_str(id),
+        }
+    }
+}
+
+impl Serialize for MCVersion {
+    fn serialize<S: Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for MCVersion {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> core::result::Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+impl Serialize for MCVersionReqSet {
+    fn serialize<S: Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for MCVersionReqSet {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> core::result::Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+impl Serialize for MCVersionReq {
+    fn serialize<S: Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for MCVersionReq {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> core::result::Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+fn parse_version(text: &str) -> Option<MCVersion> {
+    let num = |caps: &regex::Captures, i: usize| -> Option<u8> {
+        caps.get(i).map_or(Some(0), |m| m.as_str().parse().ok())
+    };
+
+    if let Some(caps) = RELEASE_REGEX.captures(text) {
+        return Some(MCVersion::Release {
+            major: num(&caps, 1)?,
+            minor: num(&caps, 2)?,
+        });
+    }
+
+    if let Some(caps) = PRE_RELEASE_REGEX.captures(text) {
+        return Some(MCVersion::PreRelease {
+            major: num(&caps, 1)?,
+            minor: num(&caps, 2)?,
+            pre: num(&caps, 3)?,
+        });
+    }
+
+    if let Some(caps) = RELEASE_CANDIDATE_REGEX.captures(text) {
+        return Some(MCVersion::ReleaseCandidate {
+            major: num(&caps, 1)?,
+            minor: num(&caps, 2)?,
+            rc: num(&caps, 3)?,
+        });
+    }
+
+    if let Some(caps) = DROP_REGEX.captures(text) {
+        let number = num(&caps, 5)?;
+        return Some(MCVersion::Drop {
+            year: num(&caps, 1)?,
+            drop: num(&caps, 2)?,
+            hotfix: num(&caps, 3)?,
+            stage: match caps.get(4).map(|m| m.as_str()) {
+                Some("snapshot") => DropStage::Snapshot(number),
+                Some("pre") => DropStage::PreRelease(number),
+                Some("rc") => DropStage::ReleaseCandidate(number),
+                _ => DropStage::Release,
+            },
+        });
     }
+
+    if let Some(caps) = SNAPSHOT_REGEX.captures(text) {
+        return Some(MCVersion::Snapshot {
+            year: num(&caps, 1)?,
+            week: num(&caps, 2)?,
+            letter: caps[3].chars().next()?,
+        });
+    }
+
+    if text.starts_with("b1.") {
+        return Some(MCVersion::OldBeta(text.to_owned()));
+    }
+
+    if ["a1.", "c0.", "inf-", "rd-"]
+        .iter()
+        .any(|prefix| text.starts_with(prefix))
+    {
+        return Some(MCVersion::OldAlpha(text.to_owned()));
+    }
+
+    None
 }
 
+/// Parses requirements such as `>=1.19.2, <1.21`, `1.20.x`, `1.20.*`, `*` or `1.19-1.20.4`
+///
+/// Comparators are separated by commas or whitespace. A version without a patch
+/// number covers the whole line, so `<=1.20` becomes `<1.21` and `>1.20` becomes `>=1.21`
+///
+/// Year-based releases work the same way and come after every `1.x` release,
+/// `>=1.21` matches `26.1` and `<=26.1` becomes `<26.2`
 impl FromStr for MCVersionReq {
     type Err = Error;
 
-    fn from_str(_text: &str) -> Result<Self> {
-        todo!()
+    fn from_str(text: &str) -> Result<Self> {
+        let invalid = || Error::InvalidVersion(text.to_owned());
+
+        let mut tokens: Vec<String> = vec![];
+        let mut pending = String::new();
+        for token in text
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|t| !t.is_empty())
+        {
+            if token == "-" {
+                // "1.19 - 1.20.4"
+                let Some(lower) = tokens.pop().filter(|_| pending.is_empty()) else {
+                    return Err(invalid());
+                };
+                pending = lower + "-";
+            } else if token.chars().all(|c| matches!(c, '<' | '>' | '=')) {
+                // ">= 1.19"
+                pending.push_str(token);
+            } else {
+                tokens.push(std::mem::take(&mut pending) + token);
+            }
+        }
+
+        if tokens.is_empty() || !pending.is_empty() {
+            return Err(invalid());
+        }
+
+        let mut comparators = vec![];
+        for token in &tokens {
+            comparators.extend(parse_comparators(token).ok_or_else(invalid)?);
+        }
+
+        Ok(Self { comparators })
+    }
+}
+
+/// Parses a single requirement token into zero (`*`), one or two (ranges) comparators
+fn parse_comparators(token: &str) -> Option<Vec<Comparator>> {
+    if token == "*" {
+        return Some(vec![]);
+    }
+
+    let (op, rest) = [
+        (">=", Op::GreaterEq),
+        ("<=", Op::LessEq),
+        (">", Op::Greater),
+        ("<", Op::Less),
+        ("=", Op::Exact),
+    ]
+    .into_iter()
+    .find_map(|(prefix, op)| token.strip_prefix(prefix).map(|rest| (Some(op), rest)))
+    .unwrap_or((None, token));
+
+    if op.is_none() {
+        if let Some((lower, upper)) = rest.split_once('-') {
+            let (lower_series, lower_major, lower_minor) = parse_partial(lower)?;
+            let (upper_series, upper_major, upper_minor) = parse_partial(upper)?;
+
+            let lower = lower_major.map(|major| Comparator {
+                op: Op::GreaterEq,
+                series: lower_series,
+                major,
+                minor: lower_minor.unwrap_or(0),
+            });
+            let upper = match (upper_major, upper_minor) {
+                (Some(major), Some(minor)) => Some((Op::LessEq, major, minor)),
+                (Some(major), None) => Some((Op::Less, major.checked_add(1)?, 0)),
+                (None, _) => None,
+            }
+            .map(|(op, major, minor)| Comparator {
+                op,
+                series: upper_series,
+                major,
+                minor,
+            });
+
+            return Some(lower.into_iter().chain(upper).collect());
+        }
+    }
+
+    let (series, major, minor) = parse_partial(rest)?;
+    let Some(major) = major else {
+        // "1.x"
+        return (op.is_none() || op == Some(Op::Exact)).then(Vec::new);
+    };
+
+    let (op, major, minor) = match (op.unwrap_or(Op::Exact), minor) {
+        (op, Some(minor)) => (op, major, minor),
+        (Op::Exact | Op::Wildcard, None) => (Op::Wildcard, major, 0),
+        (Op::GreaterEq, None) => (Op::GreaterEq, major, 0),
+        (Op::Greater, None) => (Op::GreaterEq, major.checked_add(1)?, 0),
+        (Op::Less, None) => (Op::Less, major, 0),
+        (Op::LessEq, None) => (Op::Less, major.checked_add(1)?, 0),
+    };
+
+    Some(vec![Comparator {
+        op,
+        series,
+        major,
+        minor,
+    }])
+}
+
+/// Parses `1.X`, `1.X.Y`, year-based `YY.X`, `YY.X.Y` and their wildcard forms
+/// (`1.x`, `1.X.*`, `26.1.x`) into the series and the other components.
+/// A `None` component means it was omitted or a wildcard
+fn parse_partial(text: &str) -> Option<(u8, Option<u8>, Option<u8>)> {
+    let is_wildcard = |s: &str| matches!(s, "x" | "X" | "*");
+
+    let (series, rest) = text.split_once('.')?;
+    let series = match series {
+        "1" => 1,
+        year if year.len() == 2 => year.parse().ok()?,
+        _ => return None,
+    };
+
+    let mut parts = rest.split('.');
+    let major = match parts.next()? {
+        s if is_wildcard(s) => None,
+        s => Some(s.parse().ok()?),
+    };
+    let minor = match parts.next() {
+        None => None,
+        Some(s) if is_wildcard(s) => None,
+        Some(_) if major.is_none() => return None,
+        Some(s) => Some(s.parse().ok()?),
+    };
+
+    if parts.next().is_some() {
+        return None;
+    }
+
+    if major.is_none() && series != 1 {
+        return None;
+    }
+
+    Some((series, major, minor))
+}
+
+impl Comparator {
+    /// A comparator for `1.{major}.{minor}`
+    #[must_use]
+    pub fn new(op: Op, major: u8, minor: u8) -> Self {
+        Self {
+            op,
+            series: 1,
+            major,
+            minor,
+        }
+    }
+
+    /// A comparator for year-based `{year}.{drop}.{hotfix}`
+    #[must_use]
+    pub fn year_based(op: Op, year: u8, drop: u8, hotfix: u8) -> Self {
+        Self {
+            op,
+            series: year,
+            major: drop,
+            minor: hotfix,
+        }
+    }
+
+    fn from_key(op: Op, key: u32) -> Self {
+        let (series, major, minor) = key_release(key);
+        Self {
+            op,
+            series,
+            major,
+            minor,
+        }
+    }
+}
+
+impl fmt::Display for Comparator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self {
+            op,
+            series,
+            major,
+            minor,
+        } = self;
+        match op {
+            Op::Exact => write!(f, "{series}.{major}.{minor}"),
+            Op::Wildcard => write!(f, "{series}.{major}.x"),
+            Op::GreaterEq if *minor == 0 => write!(f, ">={series}.{major}"),
+            Op::Less if *minor == 0 => write!(f, "<{series}.{major}"),
+            Op::GreaterEq => write!(f, ">={series}.{major}.{minor}"),
+            Op::Less => write!(f, "<{series}.{major}.{minor}"),
+            Op::Greater => write!(f, ">{series}.{major}.{minor}"),
+            Op::LessEq => write!(f, "<={series}.{major}.{minor}"),
+        }
+    }
+}
+
+/// Writes the canonical form of the requirement, which parses back to the same comparators
+impl fmt::Display for MCVersionReq {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.comparators.is_empty() {
+            return f.write_str("*");
+        }
+
+        for (i, cmp) in self.comparators.iter().enumerate() {
+            if i != 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{cmp}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn req(text: &str) -> MCVersionReq {
+        text.parse().unwrap()
+    }
+
+    fn version(text: &str) -> MCVersion {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn year_based_versions() {
+        for text in [
+            "26.1",
+            "26.1.1",
+            "26.1-snapshot-1",
+            "26.1-pre-1",
+            "26.1-rc-2",
+        ] {
+            let parsed = version(text);
+            assert!(matches!(parsed, MCVersion::Drop { .. }), "{text}");
+            assert_eq!(parsed.to_string(), text);
+        }
+
+        assert_eq!(version("26.1").version_type(), VersionType::Release);
+        assert_eq!(version("26.1.1").version_type(), VersionType::Release);
+        assert!(version("26.1-snapshot-1").is_snapshot());
+        assert!(version("26.1-pre-1").is_pre_release());
+        assert!(version("26.1-rc-1").is_pre_release());
+        assert_eq!(version("26.1-rc-1").version_type(), VersionType::Snapshot);
+
+        let ordered = [
+            "1.21.11",
+            "26.1-snapshot-1",
+            "26.1-snapshot-10",
+            "26.1-pre-1",
+            "26.1-rc-1",
+            "26.1",
+            "26.1.1",
+            "26.2-snapshot-1",
+        ];
+        for pair in ordered.windows(2) {
+            assert!(version(pair[0]) < version(pair[1]), "{pair:?}");
+        }
+    }
+
+    #[test]
+    fn requirement_round_trips() {
+        for (text, canonical) in [
+            (">=1.19.2, <1.21", ">=1.19.2, <1.21"),
+            ("1.20.*", "1.20.x"),
+            ("*", "*"),
+            ("1.19 - 1.20.4", ">=1.19, <=1.20.4"),
+            ("<=1.20", "<1.21"),
+        ] {
+            let parsed = req(text);
+            assert_eq!(parsed.to_string(), canonical, "{text}");
+            assert_eq!(req(&parsed.to_string()), parsed, "{text}");
+        }
+    }
+
+    #[test]
+    fn year_based_requirements() {
+        let release = version("26.1");
+        assert!(req(">=1.21").matches(&release));
+        assert!(!req("<=1.21").matches(&release));
+        assert!(req(">=26.1").matches(&version("26.1.2")));
+        assert!(req("1.21.4 - 26.1").matches(&version("26.1.1")));
+        assert!(req("26.1.x").matches(&version("26.1.2")));
+        assert!(!req("26.1.x").matches(&version("26.2")));
+        assert!(!req(">=26.1").matches(&version("26.1-rc-1")));
+
+        for (text, canonical) in [
+            (">=26.1", ">=26.1"),
+            ("<=26.1", "<26.2"),
+            ("26.1.*", "26.1.x"),
+            ("1.21.4 - 26.1", ">=1.21.4, <26.2"),
+        ] {
+            let parsed = req(text);
+            assert_eq!(parsed.to_string(), canonical, "{text}");
+            assert_eq!(req(&parsed.to_string()), parsed, "{text}");
+        }
+
+        assert_eq!(
+            req(">=1.21, <27.0").intersect(&req("26.1.x")),
+            req("26.1.x")
+        );
+        assert_eq!(
+            req(">=1.20").intersect(&req("<=26.1")).to_string(),
+            ">=1.20, <26.2"
+        );
+        assert!(req(">=26.2").intersect(&req("<26.1")).is_empty());
+
+        let set = MCVersionReqSet::from_platform_versions(["1.21.11", "26.1", "26.1.1"]);
+        assert_eq!(set.to_string(), "1.21.11 || >=26.1, <=26.1.1");
+        assert!(set.matches(&version("26.1.1")));
+        assert!(!set.matches(&version("26.1.2")));
+    }
+
+    #[test]
+    fn requirement_errors() {
+        for text in ["", ">=", "1.20-", "1.300", "26.x", "2026.1"] {
+            assert!(
+                matches!(text.parse::<MCVersionReq>(), Err(Error::InvalidVersion(_))),
+                "{text:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn requirement_set_operations() {
+        assert!(req(">=1.21, <1.20").is_empty());
+        assert!(!req("1.20.x").is_empty());
+        assert_eq!(
+            req(">=1.19, <1.21").intersect(&req("1.20.x")),
+            req("1.20.x")
+        );
+
+        let set = req("1.8.8").union(&req(">=1.16.5, <1.21"));
+        assert_eq!(set.alternatives.len(), 2);
+        assert_eq!(set.to_string(), "1.8.8 || >=1.16.5, <1.21");
+        assert!(set.matches(&MCVersion::new(8, 8)));
+        assert!(set.matches(&MCVersion::new(20, 4)));
+        assert!(!set.matches(&MCVersion::new(12, 2)));
+
+        let merged = req(">=1.19, <1.20").union(&req("1.20.x"));
+        assert_eq!(merged.alternatives, vec![req(">=1.19, <1.21")]);
     }
 }